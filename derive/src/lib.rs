@@ -0,0 +1,114 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments, Type, ext::IdentExt,
+    parse_macro_input,
+};
+
+/// Derives `k8s_config::FromDir` for a struct with named fields, so it can be read from a
+/// `DirSource` with `load`. Each field is parsed from the file named like the field, or by
+/// `#[from_dir(key = "...")]`. `Option` fields are `None` if their file is missing, all others
+/// fail the load.
+///
+/// ```ignore
+/// #[derive(k8s_config::FromDir)]
+/// pub struct Database {
+///     #[from_dir(key = "DB_HOST")]
+///     pub host: String,
+///     #[from_dir(key = "DB_PORT")]
+///     pub port: u16,
+///     pub password: Option<String>,
+/// }
+///
+/// let source = k8s_config::DirSource::new("/etc/config".into());
+/// let database: Database = source.load().unwrap();
+/// ```
+#[proc_macro_derive(FromDir, attributes(from_dir))]
+pub fn derive_from_dir(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_dir(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn from_dir(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "FromDir can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "FromDir can only be derived for structs with named fields",
+        ));
+    };
+
+    let fields = fields
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().expect("named field");
+            let key = key(field)?.unwrap_or_else(|| ident.unraw().to_string());
+            let read = if is_option(&field.ty) {
+                quote!(files.get(#key)?)
+            } else {
+                quote!(files.require(#key)?)
+            };
+            Ok(quote!(#ident: #read))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::k8s_config::FromDir for #name #ty_generics #where_clause {
+            fn from_dir(
+                files: &::k8s_config::DirFiles<'_>,
+            ) -> ::std::result::Result<Self, ::k8s_config::FromDirError> {
+                ::std::result::Result::Ok(Self {
+                    #(#fields,)*
+                })
+            }
+        }
+    })
+}
+
+/// Key set by `#[from_dir(key = "...")]`, if any.
+fn key(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut key = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("from_dir"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                key = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported from_dir attribute, expected `key`"))
+            }
+        })?;
+    }
+    Ok(key)
+}
+
+/// Whether `ty` is spelled as `Option<_>`, possibly with its path.
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    ty.qself.is_none()
+        && ty.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Option"
+                && matches!(
+                    &segment.arguments,
+                    PathArguments::AngleBracketed(args)
+                        if args.args.len() == 1
+                            && matches!(args.args[0], GenericArgument::Type(_))
+                )
+        })
+}
@@ -0,0 +1,51 @@
+#![no_main]
+
+//! Runs arbitrary bytes through the read, preprocess and parse pipeline with every combination
+//! of preprocessing options, checking that nothing panics. Run with `cargo fuzz run parse`.
+
+use std::path::PathBuf;
+
+use k8s_config::{DurationValue, FileSource, FixedKeySource, HostPort, OnEmpty};
+use libfuzzer_sys::fuzz_target;
+
+fn configure<T: std::str::FromStr + Clone>(source: &mut FileSource<T, false>, flags: u8)
+where
+    T::Err: std::fmt::Debug,
+{
+    source
+        .set_auto_trim(flags & 1 != 0)
+        .set_normalize_newlines(flags & 2 != 0)
+        .set_strip_bom(flags & 4 != 0)
+        .set_env_interpolation(flags & 8 != 0)
+        .set_max_lines((flags & 16 != 0).then_some(3))
+        .set_strip_inline_comment((flags & 32 != 0).then_some('#'));
+    if flags & 64 != 0 {
+        source.set_on_empty(Some(OnEmpty::KeepPrevious));
+    }
+}
+
+fn parse<T: std::str::FromStr + Clone>(path: &PathBuf, flags: u8)
+where
+    T::Err: std::fmt::Debug,
+{
+    let mut source: FileSource<T, false> = FileSource::from_path(path.clone());
+    configure(&mut source, flags);
+    let _ = source.refresh_value();
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, contents)) = data.split_first() else {
+        return;
+    };
+    let path = std::env::temp_dir().join(format!("k8s-config-fuzz-{}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+
+    parse::<String>(&path, flags);
+    parse::<u16>(&path, flags);
+    parse::<DurationValue>(&path, flags);
+    parse::<HostPort>(&path, flags);
+
+    let mut key = FixedKeySource::<32>::new(path.clone());
+    key.set_base64(flags & 128 != 0);
+    let _ = key.refresh_value();
+});
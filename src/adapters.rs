@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueSource};
+
+/// Exposes a required source as an optional one, turning a missing value into `None`.
+pub struct OptionalSource<S>(S);
+
+/// Exposes an optional source as a required one, turning `None` into
+/// [`ValueError::NoValue`].
+pub struct RequiredSource<S>(S);
+
+impl<S> OptionalSource<S> {
+    pub fn new(source: S) -> Self {
+        Self(source)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S> RequiredSource<S> {
+    pub fn new(source: S) -> Self {
+        Self(source)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<E: std::fmt::Debug, T, S: ValueSource<T, E>> ValueSource<Option<T>, E> for OptionalSource<S> {
+    fn value(&self) -> Result<Option<T>, ValueError<E>> {
+        match self.0.value() {
+            Ok(value) => Ok(Some(value)),
+            Err(
+                ValueError::NoValue
+                | ValueError::RefreshFileSourceError(RefreshFileSourceError::NoValue),
+            ) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T, S: ValueSource<Option<T>, E>> ValueSource<T, E> for RequiredSource<S> {
+    fn value(&self) -> Result<T, ValueError<E>> {
+        self.0.value()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, true, I> {
+    pub fn optional(self) -> OptionalSource<Self> {
+        OptionalSource::new(self)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, false, I> {
+    pub fn require(self) -> RequiredSource<Self> {
+        RequiredSource::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_as_optional() {
+        let present = FileSource::<String, true>::from_path("sources/test-required".into());
+        let missing = FileSource::<String, true>::from_path("sources/test-optional-missing".into());
+
+        assert_eq!(
+            present.optional().value().unwrap(),
+            Some("hello world!".to_owned())
+        );
+        assert_eq!(missing.optional().value().unwrap(), None);
+    }
+
+    #[test]
+    fn optional_as_required() {
+        let present = FileSource::<String, false>::from_path("sources/test-optional".into());
+        let missing =
+            FileSource::<String, false>::from_path("sources/test-optional-missing".into());
+
+        assert_eq!(present.require().value().unwrap(), "hello optional world!");
+        assert!(matches!(
+            missing.require().value(),
+            Err(ValueError::NoValue)
+        ));
+    }
+
+    #[test]
+    fn optional_keeps_other_errors() {
+        let unparseable = FileSource::<u16, true>::from_path("sources/test-required".into());
+
+        assert!(matches!(
+            unparseable.optional().value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(_)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs::Metadata,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{FileSource, RefreshFileSourceError};
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Only read files that, with symlinks resolved, lie within `root`; refreshes of anything
+    /// else fail with [`RefreshFileSourceError::PathEscape`]. Guards against path traversal
+    /// when the file name comes from partially trusted input. Entries of backends that don't
+    /// hand out files, see [`crate::Fetched::Bytes`], are not checked.
+    pub fn set_allowed_root(&mut self, root: Option<PathBuf>) -> &mut Self {
+        self.allowed_root = root;
+        self
+    }
+
+    /// Checks the file opened at `path` against the allowed root. The check runs on the open
+    /// file, so a missing file is reported as absent, and on Unix the resolved path must still
+    /// name the opened file, catching a symlink swapped between resolving and opening.
+    pub(crate) fn verify_allowed_root(
+        &self,
+        path: &Path,
+        metadata: &Metadata,
+    ) -> Result<(), RefreshFileSourceError<E>> {
+        let Some(root) = &self.allowed_root else {
+            return Ok(());
+        };
+        let resolved = path.canonicalize()?;
+        if !resolved.starts_with(root.canonicalize()?) {
+            return Err(RefreshFileSourceError::PathEscape(resolved));
+        }
+        #[cfg(unix)]
+        if crate::InodeMark::from(&std::fs::metadata(&resolved)?)
+            != crate::InodeMark::from(metadata)
+        {
+            return Err(RefreshFileSourceError::PathEscape(resolved));
+        }
+        #[cfg(not(unix))]
+        let _ = metadata;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValueError, ValueSource};
+
+    #[test]
+    fn within_root() {
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_allowed_root(Some("sources".into()));
+
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_escape_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret"), "hunter2").unwrap();
+        let link = root.path().join("config");
+        std::os::unix::fs::symlink(outside.path().join("secret"), &link).unwrap();
+
+        let mut source: FileSource<String, true> = FileSource::from_path(link);
+        source.set_allowed_root(Some(root.path().into()));
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::PathEscape(path)
+            )) if path.ends_with("secret")
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn swapped_file_rejected() {
+        use std::{fs::File, io, sync::Arc};
+
+        use crate::Opener;
+
+        // Opens another file than the one asked for, as if a symlink was swapped in between.
+        struct SwappingOpener(PathBuf);
+
+        impl Opener for SwappingOpener {
+            fn open(&self, _: &Path) -> io::Result<File> {
+                File::open(&self.0)
+            }
+        }
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("config"), "public").unwrap();
+        std::fs::write(outside.path().join("secret"), "hunter2").unwrap();
+        let mut source: FileSource<String, true> =
+            FileSource::from_path(root.path().join("config"));
+        source
+            .set_allowed_root(Some(root.path().into()))
+            .set_opener(Arc::new(SwappingOpener(outside.path().join("secret"))));
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::PathEscape(path)
+            )) if path.ends_with("config")
+        ));
+    }
+
+    #[test]
+    fn backend_keys_unchecked() {
+        use std::{io, sync::Arc};
+
+        use crate::SecretBackend;
+
+        struct PortBackend;
+
+        impl SecretBackend for PortBackend {
+            fn fetch(&self, _: &str) -> io::Result<Option<Vec<u8>>> {
+                Ok(Some(b"5432".to_vec()))
+            }
+        }
+
+        let mut source: FileSource<u16, true> = FileSource::from_path("db/port".into());
+        source
+            .set_allowed_root(Some("sources".into()))
+            .set_backend(Some(Arc::new(PortBackend)));
+
+        assert_eq!(source.value().unwrap(), 5432);
+    }
+}
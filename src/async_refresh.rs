@@ -0,0 +1,179 @@
+use std::{error::Error, future::Future, io, pin::Pin, str::FromStr, time::Duration};
+
+use futures_util::{Stream, StreamExt, stream};
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    FileSource, RefreshFileSourceError, Refreshable, ValueError, ValueOutcome,
+    backend::{ReadRecord, read_entry},
+};
+
+pub type RefreshFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Async counterpart of [`Refreshable`].
+pub trait AsyncRefreshable: Refreshable {
+    fn refresh_async(&self) -> RefreshFuture<'_>;
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Async version of [`FileSource::refresh_value`], fetching from the backend on tokio's
+    /// blocking thread pool.
+    ///
+    /// Cancellation safe: the cache is only updated once the file was completely read and
+    /// parsed, so dropping the future mid-read keeps the previous value.
+    pub async fn refresh_value_async(&self) -> Result<(), RefreshFileSourceError<E>> {
+        if self.refresh_paused() {
+            return Ok(());
+        }
+
+        let started = self.clock.now();
+        let mut record = ReadRecord::default();
+        let read = self.read_value_async(&mut record).await;
+        let committed = self.commit(read, record);
+        self.finish_refresh(started, committed)
+    }
+
+    pub async fn refresh_on_timeout_async(&self) -> Result<(), RefreshFileSourceError<E>> {
+        if self.needs_refresh() && !self.retry_throttled() {
+            let result = self.refresh_value_async().await;
+            self.record_refresh_attempt(result)?;
+        }
+
+        Ok(())
+    }
+
+    async fn read_value_async(
+        &self,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        // Backends are blocking, so they must not run on the executor's threads.
+        let backend = self.backend.clone();
+        let location = self.location();
+        let read = tokio::task::spawn_blocking(move || read_entry(&*backend, &location))
+            .await
+            .map_err(io::Error::other)??;
+
+        self.parse_raw(read.as_ref(), record)
+    }
+
+    /// Refreshes every `interval`, starting immediately, yielding the outcome of each refresh.
+    fn refresh_outcomes(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<ValueOutcome<T>, ValueError<E>>> + '_ {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        stream::unfold(ticker, move |mut ticker| async move {
+            ticker.tick().await;
+            let outcome = match self.refresh_value_async().await {
+                Ok(()) => Ok(self.cached()),
+                Err(e) => Err(e.into()),
+            };
+            Some((outcome, ticker))
+        })
+    }
+}
+
+impl<E, T, const REQUIRED: bool, I> AsyncRefreshable for FileSource<T, REQUIRED, I>
+where
+    E: Error + Send + Sync + 'static,
+    T: FromStr<Err = E> + Clone + Send + Sync,
+{
+    fn refresh_async(&self) -> RefreshFuture<'_> {
+        Box::pin(async move { Ok(self.refresh_value_async().await?) })
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, true, I> {
+    pub async fn value_async(&self) -> Result<T, ValueError<E>> {
+        self.refresh_on_timeout_async().await?;
+        self.cached().into_option().ok_or(ValueError::NoValue)
+    }
+
+    /// Endless stream refreshing every `interval` and yielding each result, errors included.
+    pub fn refresh_stream(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<T, ValueError<E>>> + '_ {
+        self.refresh_outcomes(interval)
+            .map(|outcome| outcome?.into_option().ok_or(ValueError::NoValue))
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, false, I> {
+    pub async fn value_async(&self) -> Result<Option<T>, ValueError<E>> {
+        self.refresh_on_timeout_async().await?;
+        Ok(self.cached().into_option())
+    }
+
+    /// Endless stream refreshing every `interval` and yielding each result, errors included.
+    pub fn refresh_stream(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Option<T>, ValueError<E>>> + '_ {
+        self.refresh_outcomes(interval)
+            .map(|outcome| Ok(outcome?.into_option()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    #[tokio::test]
+    async fn value_async_reads() {
+        let source: FileSource<u16, false> = FileSource::from_path("sources/test-port".into());
+
+        assert_eq!(source.value_async().await.unwrap(), Some(8080));
+    }
+
+    #[tokio::test]
+    async fn refresh_stream_yields_each_refresh() {
+        let source: TestSource<u16, true> = TestSource::new("8080");
+        let mut stream = std::pin::pin!(source.refresh_stream(Duration::from_millis(10)));
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 8080);
+        source.write("not a port");
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(_)
+            ))
+        ));
+        source.write("9090");
+        assert_eq!(stream.next().await.unwrap().unwrap(), 9090);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn dropped_refresh_keeps_cache() {
+        use nix::{sys::stat::Mode, unistd::mkfifo};
+        use tokio::io::AsyncWriteExt;
+
+        let source: TestSource<String, true> = TestSource::new("first");
+        assert_eq!(source.value_async().await.unwrap(), "first");
+
+        // A FIFO blocks the read until a writer shows up, keeping the refresh in flight
+        source.remove();
+        mkfifo(source.path(), Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+        let refresh =
+            tokio::time::timeout(Duration::from_millis(100), source.refresh_value_async());
+        assert!(refresh.await.is_err());
+        assert_eq!(source.value().unwrap(), "first");
+
+        // Unblock the abandoned read so the runtime can shut down
+        let mut writer = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(source.path())
+            .await
+            .unwrap();
+        writer.write_all(b"second").await.unwrap();
+        drop(writer);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(source.value().unwrap(), "first");
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    fs::{File, Metadata},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, LazyLock},
+    time::SystemTime,
+};
+
+use crate::{
+    FileSource, FsOpener, Location, Opener, RefreshFileSourceError, ValueOutcome,
+    skip_unchanged::ReadMark,
+};
+
+/// Entry a [`SecretBackend`] found under a key.
+pub enum Fetched {
+    /// A file opened at `path`. File specific checks such as the allowed root, inode tracking,
+    /// permission checks and memory mapping only apply to these.
+    File { file: File, path: PathBuf },
+    /// Contents fetched from elsewhere, with their modified time if the store keeps one.
+    Bytes {
+        contents: Vec<u8>,
+        modified: Option<SystemTime>,
+    },
+}
+
+/// Store that secrets and config values are fetched from by key, so a source can be backed by
+/// files today and by e.g. Vault or a CSI driver later without changing consumer code.
+pub trait SecretBackend: Send + Sync {
+    /// Contents stored under `key`, `None` if there is no such entry.
+    fn fetch(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Entry stored under `key`, `None` if there is no such entry. Override this to hand out
+    /// open files or modified times; the default wraps [`SecretBackend::fetch`].
+    fn open(&self, key: &str) -> io::Result<Option<Fetched>> {
+        Ok(self.fetch(key)?.map(|contents| Fetched::Bytes {
+            contents,
+            modified: None,
+        }))
+    }
+
+    /// Entry for a source reading `path`, looked up with the path as key by default.
+    fn open_path(&self, path: &Path) -> io::Result<Option<Fetched>> {
+        self.open(&path.to_string_lossy())
+    }
+
+    /// Identifies the entry [`SecretBackend::open_path`] opens for `path`, so sources spelling
+    /// the same entry differently share reads through a [`crate::SharedReadCache`]. The path
+    /// itself by default.
+    fn entry_key(&self, path: &Path) -> PathBuf {
+        path.to_owned()
+    }
+}
+
+/// [`SecretBackend`] reading the file at the key's path, relative to an optional root. The
+/// default backend of every source.
+#[derive(Clone)]
+pub struct FsBackend {
+    root: Option<PathBuf>,
+    opener: Arc<dyn Opener>,
+}
+
+impl Default for FsBackend {
+    fn default() -> Self {
+        Self {
+            root: None,
+            opener: Arc::new(FsOpener),
+        }
+    }
+}
+
+impl std::fmt::Debug for FsBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FsBackend")
+            .field("root", &self.root)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FsBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves keys relative to `root` instead of the working directory.
+    pub fn with_root(root: PathBuf) -> Self {
+        Self {
+            root: Some(root),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_opener(&mut self, opener: Arc<dyn Opener>) -> &mut Self {
+        self.opener = opener;
+        self
+    }
+
+    fn open_file(&self, path: &Path) -> io::Result<Option<(File, PathBuf)>> {
+        let path = match &self.root {
+            Some(root) => root.join(path),
+            None => path.to_owned(),
+        };
+        // Branching on the open error rather than checking `exists()` first saves a syscall
+        // and avoids racing with the file being removed in between.
+        match self.opener.open(&path) {
+            Ok(file) => Ok(Some((file, path))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl SecretBackend for FsBackend {
+    fn fetch(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some((mut file, _)) = self.open_file(Path::new(key))? else {
+            return Ok(None);
+        };
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        Ok(Some(contents))
+    }
+
+    fn open(&self, key: &str) -> io::Result<Option<Fetched>> {
+        self.open_path(Path::new(key))
+    }
+
+    fn open_path(&self, path: &Path) -> io::Result<Option<Fetched>> {
+        Ok(self
+            .open_file(path)?
+            .map(|(file, path)| Fetched::File { file, path }))
+    }
+
+    /// The canonicalized path for backends reading relative to the working directory.
+    fn entry_key(&self, path: &Path) -> PathBuf {
+        match &self.root {
+            Some(_) => path.to_owned(),
+            None => std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned()),
+        }
+    }
+}
+
+/// The [`FsBackend`] of all sources without their own, so sources sharing a
+/// [`crate::SharedReadCache`] also share reads through it.
+pub(crate) fn default_backend() -> Arc<dyn SecretBackend> {
+    static DEFAULT: LazyLock<Arc<dyn SecretBackend>> = LazyLock::new(|| Arc::new(FsBackend::new()));
+    DEFAULT.clone()
+}
+
+/// What a read learned about the file, applied to the source only once a refresh commits it,
+/// so dry runs such as [`FileSource::validate`] leave the source untouched.
+#[derive(Default)]
+pub(crate) struct ReadRecord {
+    /// Dry runs also don't take the shortcuts relying on the source's state, skipping unchanged
+    /// files and reusing the memory mapping.
+    pub(crate) dry_run: bool,
+    pub(crate) bytes: Option<usize>,
+    modified: Option<SystemTime>,
+    pub(crate) mark: Option<ReadMark>,
+    /// Whether the entry was found, `None` if the read failed before finding out.
+    pub(crate) present: Option<bool>,
+    /// Hash of the contents parsed, `None` if nothing was parsed.
+    pub(crate) fingerprint: Option<u64>,
+    #[cfg(unix)]
+    metadata: Option<Metadata>,
+}
+
+impl ReadRecord {
+    pub(crate) fn dry_run() -> Self {
+        Self {
+            dry_run: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// File an entry was read from.
+pub(crate) struct OpenedFile {
+    /// Path the file was opened at.
+    pub(crate) path: PathBuf,
+    /// Taken from the open handle, so it describes the file read even if it was replaced.
+    pub(crate) metadata: Metadata,
+}
+
+/// Entry read completely, detached from the source so it can be read on another thread.
+pub(crate) struct RawRead {
+    pub(crate) contents: Vec<u8>,
+    /// `None` for entries that aren't files.
+    pub(crate) file: Option<OpenedFile>,
+    pub(crate) modified: Option<SystemTime>,
+}
+
+/// Looks up the path of `location` in `backend` and reads the entry completely.
+pub(crate) fn read_entry(
+    backend: &dyn SecretBackend,
+    location: &Location,
+) -> io::Result<Option<RawRead>> {
+    Ok(match backend.open_path(&location.path())? {
+        None => None,
+        Some(Fetched::Bytes { contents, modified }) => Some(RawRead {
+            contents,
+            file: None,
+            modified,
+        }),
+        Some(Fetched::File { mut file, path }) => {
+            let metadata = file.metadata()?;
+            let mut contents = Vec::with_capacity(metadata.len().try_into().unwrap_or(0));
+            location.rewind(&mut file)?;
+            file.read_to_end(&mut contents)?;
+            Some(RawRead {
+                contents,
+                modified: metadata.modified().ok(),
+                file: Some(OpenedFile { path, metadata }),
+            })
+        }
+    })
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Read through `backend`, using the source's path as key, instead of the default
+    /// [`FsBackend`]; `None` restores the default. The allowed root, inode tracking, permission
+    /// checks and memory mapping only apply to entries handed out as open files, see
+    /// [`Fetched::File`].
+    pub fn set_backend(&mut self, backend: Option<Arc<dyn SecretBackend>>) -> &mut Self {
+        self.backend = backend.unwrap_or_else(default_backend);
+        self
+    }
+
+    /// Records what was read about the entry, before its contents are parsed.
+    pub(crate) fn record_read(
+        &self,
+        record: &mut ReadRecord,
+        metadata: Option<&Metadata>,
+        modified: Option<SystemTime>,
+    ) {
+        record.modified = modified;
+        record.mark = metadata.map(ReadMark::from);
+        #[cfg(unix)]
+        {
+            record.metadata = metadata.cloned();
+        }
+        #[cfg(not(unix))]
+        let _ = metadata;
+        record.present = Some(true);
+    }
+
+    /// Applies what a committed read learned about the file.
+    pub(crate) fn apply_record(&self, record: &ReadRecord) {
+        if let Some(bytes) = record.bytes {
+            self.set_last_read_bytes(bytes);
+        }
+        self.record_modified(record.modified);
+        match record.present {
+            Some(true) => self.record_present(),
+            Some(false) => self.record_absent(),
+            None => {}
+        }
+        #[cfg(unix)]
+        if let Some(metadata) = &record.metadata {
+            self.record_inode(metadata);
+            self.check_permissions(metadata);
+        }
+    }
+
+    /// Parses an entry read completely, e.g. on a helper thread.
+    pub(crate) fn parse_raw(
+        &self,
+        read: Option<&RawRead>,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        let Some(read) = read else {
+            return self.missing(record);
+        };
+        let metadata = read.file.as_ref().map(|file| &file.metadata);
+        if let Some(file) = &read.file {
+            self.verify_allowed_root(&file.path, &file.metadata)?;
+        }
+        self.record_read(record, metadata, read.modified);
+        if let (false, Some(metadata)) = (record.dry_run, metadata)
+            && let Some(cached) = self.cached_if_unchanged(metadata)
+        {
+            return Ok(cached);
+        }
+        self.parse_bytes(&read.contents, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::RwLock, time::Duration};
+
+    use super::*;
+    use crate::{ValueError, ValueSource};
+
+    #[derive(Default)]
+    struct MemoryBackend(RwLock<HashMap<String, Vec<u8>>>);
+
+    impl MemoryBackend {
+        fn insert(&self, key: &str, contents: &str) {
+            self.0
+                .write()
+                .unwrap()
+                .insert(key.to_owned(), contents.as_bytes().to_vec());
+        }
+    }
+
+    impl SecretBackend for MemoryBackend {
+        fn fetch(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.0.read().unwrap().get(key).cloned())
+        }
+    }
+
+    #[test]
+    fn memory_backend() {
+        let backend = Arc::new(MemoryBackend::default());
+        let mut source: FileSource<u16, true> = FileSource::from_path("db/port".into());
+        source.set_backend(Some(backend.clone()));
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::NoValue
+            ))
+        ));
+
+        backend.insert("db/port", "5432\n");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), 5432);
+    }
+
+    #[derive(Default)]
+    struct StampedBackend(RwLock<Option<(Vec<u8>, SystemTime)>>);
+
+    impl SecretBackend for StampedBackend {
+        fn fetch(&self, _: &str) -> io::Result<Option<Vec<u8>>> {
+            unreachable!("open is overridden")
+        }
+
+        fn open(&self, _: &str) -> io::Result<Option<Fetched>> {
+            Ok(self
+                .0
+                .read()
+                .unwrap()
+                .clone()
+                .map(|(contents, modified)| Fetched::Bytes {
+                    contents,
+                    modified: Some(modified),
+                }))
+        }
+    }
+
+    #[test]
+    fn backend_modified_time() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let backend = Arc::new(StampedBackend::default());
+        *backend.0.write().unwrap() = Some((b"5432".to_vec(), modified));
+        let mut source: FileSource<u16, true> = FileSource::from_path("db/port".into());
+        source
+            .set_backend(Some(backend))
+            .set_read_timeout(Some(Duration::from_secs(1)));
+
+        assert_eq!(source.value_stamped().unwrap(), (5432, modified));
+    }
+
+    #[test]
+    fn fs_backend() {
+        let mut source: FileSource<u16, false> = FileSource::from_path("test-port".into());
+        source.set_backend(Some(Arc::new(FsBackend::with_root("sources".into()))));
+
+        assert_eq!(source.value().unwrap(), Some(8080));
+    }
+}
@@ -0,0 +1,115 @@
+use std::{str::FromStr, sync::Arc};
+
+use crate::{FileSource, RefreshFileSourceError, ValueOutcome, backend::ReadRecord};
+
+pub type ReloadCallback<T> = Box<dyn Fn(&[T]) + Send + Sync>;
+
+/// Sources that only reload as a unit, e.g. a certificate, its key and the CA forming one
+/// trust config.
+///
+/// [`ReloadBarrier::reload`] reads every source before storing any value, so one failing
+/// source keeps all of them at their previous values, and the reload callback only sees
+/// values that belong together.
+pub struct ReloadBarrier<T: FromStr + Clone>
+where
+    T::Err: std::fmt::Debug,
+{
+    sources: Vec<Arc<FileSource<T, true>>>,
+    on_reload: ReloadCallback<T>,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ReloadBarrier<T> {
+    pub fn new(on_reload: ReloadCallback<T>) -> Self {
+        Self {
+            sources: Vec::new(),
+            on_reload,
+        }
+    }
+
+    pub fn add(&mut self, source: Arc<FileSource<T, true>>) -> &mut Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Reads all sources and, if every read succeeded, stores the new values and calls the
+    /// reload callback with them in the order the sources were added. Otherwise returns the
+    /// first error, leaving every source at its previous value.
+    pub fn reload(&self) -> Result<(), RefreshFileSourceError<E>> {
+        let mut values = Vec::with_capacity(self.sources.len());
+        let mut records = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let mut record = ReadRecord::default();
+            match source.read_value(&mut record)? {
+                ValueOutcome::Loaded(value) => {
+                    values.push(value);
+                    records.push(record);
+                }
+                ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => {
+                    return Err(RefreshFileSourceError::NoValue);
+                }
+            }
+        }
+
+        let previous: Vec<_> = self.sources.iter().map(|source| source.cached()).collect();
+        let reads = self.sources.iter().zip(&values).zip(records);
+        for (index, ((source, value), record)) in reads.enumerate() {
+            if let Err(e) = source.commit(Ok(ValueOutcome::Loaded(value.clone())), record) {
+                for (source, previous) in self.sources[..index].iter().zip(&previous) {
+                    *source
+                        .value
+                        .write()
+                        .expect("error locking value for FileSource") = previous.clone();
+                }
+                return Err(e);
+            }
+        }
+
+        (self.on_reload)(&values);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::ValueSource;
+
+    #[test]
+    fn all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = ["cert", "key", "ca"].map(|name| dir.path().join(name));
+        for path in &paths {
+            std::fs::write(path, "v1").unwrap();
+        }
+        let sources = paths
+            .clone()
+            .map(|path| Arc::new(FileSource::<String, true>::from_path(path)));
+        let reloads = Arc::new(Mutex::new(Vec::new()));
+        let mut barrier = ReloadBarrier::new(Box::new({
+            let reloads = reloads.clone();
+            move |values: &[String]| reloads.lock().unwrap().push(values.to_vec())
+        }));
+        for source in &sources {
+            barrier.add(source.clone());
+        }
+        barrier.reload().unwrap();
+
+        std::fs::write(&paths[0], "v2").unwrap();
+        std::fs::write(&paths[1], "v2").unwrap();
+        std::fs::remove_file(&paths[2]).unwrap();
+        assert!(matches!(
+            barrier.reload(),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+        for source in &sources {
+            assert_eq!(source.value().unwrap(), "v1");
+        }
+        assert_eq!(*reloads.lock().unwrap(), [["v1", "v1", "v1"]]);
+
+        std::fs::write(&paths[2], "v2").unwrap();
+        barrier.reload().unwrap();
+        assert_eq!(reloads.lock().unwrap()[1], ["v2", "v2", "v2"]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::{num::ParseIntError, path::PathBuf, str::FromStr};
+
+use crate::{FileSource, ValueError, ValueSource};
+
+/// Memory limit of the container under cgroup v2.
+pub const CGROUP_MEMORY_MAX: &str = "/sys/fs/cgroup/memory.max";
+
+/// Value of a cgroup limit file: a number, or `max` for no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CgroupLimit(pub Option<u64>);
+
+impl FromStr for CgroupLimit {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max" => Ok(Self(None)),
+            limit => limit.parse().map(|limit| Self(Some(limit))),
+        }
+    }
+}
+
+/// Source for a cgroup limit such as `memory.max`, `None` if unlimited.
+pub struct CgroupLimitSource {
+    source: FileSource<CgroupLimit, true>,
+}
+
+impl CgroupLimitSource {
+    pub fn new(source: FileSource<CgroupLimit, true>) -> Self {
+        Self { source }
+    }
+
+    /// Source for the pod's memory limit at [`CGROUP_MEMORY_MAX`].
+    pub fn memory_max() -> Self {
+        Self::new(FileSource::from_path(PathBuf::from(CGROUP_MEMORY_MAX)))
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<CgroupLimit, true> {
+        &mut self.source
+    }
+}
+
+impl ValueSource<Option<u64>, ParseIntError> for CgroupLimitSource {
+    fn value(&self) -> Result<Option<u64>, ValueError<ParseIntError>> {
+        Ok(self.source.value()?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_limit() {
+        let source =
+            CgroupLimitSource::new(FileSource::from_path("sources/test-cgroup-limit".into()));
+
+        assert_eq!(source.value().unwrap(), Some(536870912));
+    }
+
+    #[test]
+    fn unlimited() {
+        let source =
+            CgroupLimitSource::new(FileSource::from_path("sources/test-cgroup-max".into()));
+
+        assert_eq!(source.value().unwrap(), None);
+        assert!("-1".parse::<CgroupLimit>().is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+use crate::{FileSource, RefreshFileSourceError};
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Refuse files whose SHA-256 differs from the hex encoded `sha256`, failing the refresh
+    /// with [`RefreshFileSourceError::ChecksumMismatch`] and keeping the previous value. The
+    /// hash covers the raw bytes as stored, before gzip decompression, decoding and any other
+    /// preprocessing, so it matches the output of `sha256sum` on the file.
+    pub fn set_expected_sha256(&mut self, sha256: Option<&str>) -> &mut Self {
+        self.expected_sha256 = sha256.map(str::to_ascii_lowercase);
+        self
+    }
+
+    pub(crate) fn verify_checksum(&self, bytes: &[u8]) -> Result<(), RefreshFileSourceError<E>> {
+        let Some(expected) = &self.expected_sha256 else {
+            return Ok(());
+        };
+        let actual: String = Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        if actual == *expected {
+            Ok(())
+        } else {
+            Err(RefreshFileSourceError::ChecksumMismatch {
+                expected: expected.clone(),
+                actual,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    const TEST_PORT_SHA256: &str =
+        "5EAB4B7190201CF7F6308297EF8EAE74089AAE672402847A0C8AF46370CA365C";
+
+    #[test]
+    fn matching_checksum() {
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_expected_sha256(Some(TEST_PORT_SHA256));
+
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[test]
+    fn mismatch_keeps_previous() {
+        let mut source: TestSource<u16, true> = TestSource::new("8080\n");
+        source.set_expected_sha256(Some(TEST_PORT_SHA256));
+        assert_eq!(source.value().unwrap(), 8080);
+
+        source.write("9090\n");
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::ChecksumMismatch { expected, .. })
+                if expected == TEST_PORT_SHA256.to_ascii_lowercase()
+        ));
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[test]
+    fn hashes_raw_bytes() {
+        let mut source: TestSource<u16, true> = TestSource::new("\u{FEFF}8080\n");
+        source.set_expected_sha256(Some(TEST_PORT_SHA256));
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::ChecksumMismatch { .. })
+        ));
+
+        let raw: String = Sha256::digest(std::fs::read(source.path()).unwrap())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        source.set_expected_sha256(Some(&raw));
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+}
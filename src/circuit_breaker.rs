@@ -0,0 +1,153 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{Clock, SystemClock, ValueError, ValueSource};
+
+/// Wraps a source and stops calling it for a cooldown period after too many consecutive
+/// failures. While open, the last good value is served, or [`ValueError::CircuitOpen`] if
+/// there never was one.
+pub struct CircuitBreakerSource<S, T> {
+    source: S,
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState<T>>,
+    clock: Arc<dyn Clock>,
+}
+
+struct BreakerState<T> {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    last_good: Option<T>,
+}
+
+impl<S, T> CircuitBreakerSource<S, T> {
+    pub fn new(source: S, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            source,
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                opened_at: None,
+                last_good: None,
+            }),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state
+            .lock()
+            .expect("error locking CircuitBreakerSource state")
+            .opened_at
+            .is_some_and(|opened_at| self.clock.now() < opened_at + self.cooldown)
+    }
+}
+
+impl<E: std::fmt::Debug, T: Clone, S: ValueSource<T, E>> ValueSource<T, E>
+    for CircuitBreakerSource<S, T>
+{
+    fn value(&self) -> Result<T, ValueError<E>> {
+        {
+            let state = self
+                .state
+                .lock()
+                .expect("error locking CircuitBreakerSource state");
+            if state
+                .opened_at
+                .is_some_and(|opened_at| self.clock.now() < opened_at + self.cooldown)
+            {
+                return state.last_good.clone().ok_or(ValueError::CircuitOpen);
+            }
+        }
+
+        let result = self.source.value();
+        let mut state = self
+            .state
+            .lock()
+            .expect("error locking CircuitBreakerSource state");
+        match &result {
+            Ok(value) => {
+                state.consecutive_failures = 0;
+                state.opened_at = None;
+                state.last_good = Some(value.clone());
+            }
+            Err(_) => {
+                state.consecutive_failures += 1;
+                if state.consecutive_failures >= self.failure_threshold {
+                    state.opened_at = Some(self.clock.now());
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::MockClock;
+
+    #[derive(Default)]
+    struct FlakySource {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl ValueSource<u16, ()> for &FlakySource {
+        fn value(&self) -> Result<u16, ValueError<()>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                Err(ValueError::NoValue)
+            } else {
+                Ok(8080)
+            }
+        }
+    }
+
+    #[test]
+    fn trips_and_recovers() {
+        let flaky = FlakySource::default();
+        flaky.failing.store(true, Ordering::SeqCst);
+        let clock = MockClock::new();
+        let mut breaker = CircuitBreakerSource::new(&flaky, 3, Duration::from_secs(30));
+        breaker.set_clock(Arc::new(clock.clone()));
+
+        for _ in 0..3 {
+            assert!(matches!(breaker.value(), Err(ValueError::NoValue)));
+        }
+        assert!(breaker.is_open());
+        assert!(matches!(breaker.value(), Err(ValueError::CircuitOpen)));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+
+        flaky.failing.store(false, Ordering::SeqCst);
+        clock.advance(Duration::from_secs(31));
+        assert_eq!(breaker.value().unwrap(), 8080);
+        assert!(!breaker.is_open());
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn serves_last_good_while_open() {
+        let flaky = FlakySource::default();
+        let clock = MockClock::new();
+        let mut breaker = CircuitBreakerSource::new(&flaky, 1, Duration::from_secs(30));
+        breaker.set_clock(Arc::new(clock.clone()));
+        assert_eq!(breaker.value().unwrap(), 8080);
+
+        flaky.failing.store(true, Ordering::SeqCst);
+        assert!(breaker.value().is_err());
+        assert_eq!(breaker.value().unwrap(), 8080);
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Source of the current time used for refresh bookkeeping.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time, for policies based on the time of day.
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually advanced clock for tests; clones share the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<(Instant, SystemTime)>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new((Instant::now(), SystemTime::now()))),
+        }
+    }
+
+    /// Advances both the monotonic and the wall-clock time.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().expect("error locking MockClock");
+        now.0 += duration;
+        now.1 += duration;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.now.lock().expect("error locking MockClock").0
+    }
+
+    fn system_time(&self) -> SystemTime {
+        self.now.lock().expect("error locking MockClock").1
+    }
+}
@@ -0,0 +1,151 @@
+use std::{marker::PhantomData, str::FromStr};
+
+use crate::{
+    EnvLookup, FileSource, RefreshFileSourceError, ValueError, ValueOutcome, ValueSource,
+    interpolate::lookup_env,
+};
+
+/// Which part comes first in a [`ConcatSource`] value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConcatOrder {
+    #[default]
+    EnvFirst,
+    FileFirst,
+}
+
+/// Source concatenating an environment variable and a file before parsing, for values with a
+/// small dynamic part set in the environment and the bulk mounted as a file.
+///
+/// An unset environment variable contributes nothing; whether the file must exist follows
+/// `REQUIRED`. The environment is read on each access, the file through the cache.
+pub struct ConcatSource<T, const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    env_var: String,
+    order: ConcatOrder,
+    separator: String,
+    env_lookup: Option<EnvLookup>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T: FromStr, const REQUIRED: bool> ConcatSource<T, REQUIRED>
+where
+    T::Err: std::fmt::Debug,
+{
+    pub fn new(env_var: impl Into<String>, source: FileSource<String, REQUIRED>) -> Self {
+        Self {
+            source,
+            env_var: env_var.into(),
+            order: ConcatOrder::default(),
+            separator: String::new(),
+            env_lookup: None,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    pub fn set_order(&mut self, order: ConcatOrder) -> &mut Self {
+        self.order = order;
+        self
+    }
+
+    /// Inserted between the two parts when both are present.
+    pub fn set_separator(&mut self, separator: impl Into<String>) -> &mut Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Read the variable with `lookup` instead of the process environment, `None` restores the
+    /// process environment.
+    pub fn set_env_lookup(&mut self, lookup: Option<EnvLookup>) -> &mut Self {
+        self.env_lookup = lookup;
+        self
+    }
+
+    fn parsed(&self) -> Result<Option<T>, ValueError<T::Err>> {
+        let ValueOutcome::Loaded(contents) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+        let combined = match (
+            lookup_env(self.env_lookup.as_ref(), &self.env_var),
+            self.order,
+        ) {
+            (None, _) => contents,
+            (Some(env), ConcatOrder::EnvFirst) => [env, contents].join(&self.separator),
+            (Some(env), ConcatOrder::FileFirst) => [contents, env].join(&self.separator),
+        };
+
+        Ok(Some(
+            combined
+                .parse()
+                .map_err(RefreshFileSourceError::ParseError)?,
+        ))
+    }
+}
+
+impl<T: FromStr> ValueSource<T, T::Err> for ConcatSource<T, true>
+where
+    T::Err: std::fmt::Debug,
+{
+    fn value(&self) -> Result<T, ValueError<T::Err>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<T: FromStr> ValueSource<Option<T>, T::Err> for ConcatSource<T, false>
+where
+    T::Err: std::fmt::Debug,
+{
+    fn value(&self) -> Result<Option<T>, ValueError<T::Err>> {
+        self.parsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn env(name: &'static str, value: &'static str) -> Option<EnvLookup> {
+        Some(Arc::new(move |var| (var == name).then(|| value.to_owned())))
+    }
+
+    #[test]
+    fn env_prefix_and_file() {
+        let mut source: ConcatSource<u32, true> = ConcatSource::new(
+            "K8S_CONFIG_TEST_CONCAT_PREFIX",
+            FileSource::from_path("sources/test-port".into()),
+        );
+        source.set_env_lookup(env("K8S_CONFIG_TEST_CONCAT_PREFIX", "1"));
+
+        assert_eq!(source.value().unwrap(), 18080);
+    }
+
+    #[test]
+    fn order_and_separator() {
+        let mut source: ConcatSource<String, true> = ConcatSource::new(
+            "K8S_CONFIG_TEST_CONCAT_SUFFIX",
+            FileSource::from_path("sources/test-required".into()),
+        );
+        source
+            .set_order(ConcatOrder::FileFirst)
+            .set_separator(" ")
+            .set_env_lookup(env("K8S_CONFIG_TEST_CONCAT_SUFFIX", "bye"));
+
+        assert_eq!(source.value().unwrap(), "hello world! bye");
+    }
+
+    #[test]
+    fn unset_env_is_file_only() {
+        let mut source: ConcatSource<u16, false> = ConcatSource::new(
+            "K8S_CONFIG_TEST_CONCAT_UNSET",
+            FileSource::from_path("sources/test-port".into()),
+        );
+        source.set_env_lookup(env("K8S_CONFIG_TEST_CONCAT_PREFIX", "1"));
+
+        assert_eq!(source.value().unwrap(), Some(8080));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{str::FromStr, sync::Arc};
+
+use crate::{FileSource, ValueError, ValueSource};
+
+/// Optional source that becomes required depending on a predicate evaluated on every read,
+/// e.g. `CERT_FILE` being required unless `CERT_INLINE` is set.
+pub struct ConditionallyRequiredSource<T: FromStr + Clone>
+where
+    T::Err: std::fmt::Debug,
+{
+    source: FileSource<T, false>,
+    is_required: Box<dyn Fn() -> bool + Send + Sync>,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ConditionallyRequiredSource<T> {
+    pub fn new(
+        source: FileSource<T, false>,
+        is_required: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            source,
+            is_required: Box::new(is_required),
+        }
+    }
+
+    /// Requires a value unless `companion` provides one; a failing companion counts as
+    /// providing none.
+    pub fn required_unless<U: 'static, E2: std::fmt::Debug + 'static>(
+        source: FileSource<T, false>,
+        companion: Arc<dyn ValueSource<Option<U>, E2> + Send + Sync>,
+    ) -> Self {
+        Self::new(source, move || !matches!(companion.value(), Ok(Some(_))))
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<T, false> {
+        &mut self.source
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<Option<T>, E>
+    for ConditionallyRequiredSource<T>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<E>> {
+        match self.source.value()? {
+            None if (self.is_required)() => Err(ValueError::NoValue),
+            value => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert_file(companion_path: &str) -> ConditionallyRequiredSource<String> {
+        let companion: Arc<FileSource<String, false>> =
+            Arc::new(FileSource::from_path(companion_path.into()));
+        ConditionallyRequiredSource::required_unless(
+            FileSource::from_path("sources/test-optional-missing".into()),
+            companion,
+        )
+    }
+
+    #[test]
+    fn errors_without_companion() {
+        let source = cert_file("sources/test-optional-missing");
+
+        assert!(matches!(source.value(), Err(ValueError::NoValue)));
+    }
+
+    #[test]
+    fn absent_with_companion() {
+        let source = cert_file("sources/test-optional");
+
+        assert_eq!(source.value().unwrap(), None);
+    }
+
+    #[test]
+    fn present_value() {
+        let source = ConditionallyRequiredSource::new(
+            FileSource::<String, false>::from_path("sources/test-optional".into()),
+            || true,
+        );
+
+        assert_eq!(
+            source.value().unwrap(),
+            Some("hello optional world!".to_owned())
+        );
+    }
+}
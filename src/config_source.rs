@@ -0,0 +1,82 @@
+use config::{ConfigError, Map, Source, Value, ValueKind};
+
+use crate::{ConfigValue, SourceRegistry};
+
+/// Layer for a `config::Config` builder, reading every registered source on each build. Names
+/// are used as config keys, so `server.port` ends up nested under `server`.
+impl Source for SourceRegistry {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let mut values = Map::new();
+        for name in self.names() {
+            let source = self.get(name).expect("name was just listed");
+            let Some(value) = source.erased_value().map_err(ConfigError::Foreign)? else {
+                continue;
+            };
+            let kind = match value {
+                ConfigValue::String(value) => ValueKind::String(value),
+                ConfigValue::Int(value) => ValueKind::I64(value),
+                ConfigValue::Bool(value) => ValueKind::Boolean(value),
+                ConfigValue::Duration(value) => {
+                    ValueKind::String(format!("{}ms", value.as_millis()))
+                }
+            };
+            let origin = source.path().display().to_string();
+            values.insert(name.to_owned(), Value::new(Some(&origin), kind));
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use config::Config;
+
+    use crate::{FileSource, SourceRegistry};
+
+    #[test]
+    fn registry_as_config_layer() {
+        let mut registry = SourceRegistry::new();
+        registry
+            .insert(
+                "server.port",
+                Arc::new(FileSource::<i64, true>::from_path(
+                    "sources/test-port".into(),
+                )),
+            )
+            .insert(
+                "server.greeting",
+                Arc::new(FileSource::<String, true>::from_path(
+                    "sources/test-required".into(),
+                )),
+            )
+            .insert(
+                "server.missing",
+                Arc::new(FileSource::<String, false>::from_path(
+                    "sources/test-optional-missing".into(),
+                )),
+            );
+
+        let config = Config::builder()
+            .set_default("server.port", 80)
+            .unwrap()
+            .set_default("server.name", "default")
+            .unwrap()
+            .add_source(registry)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get::<u16>("server.port").unwrap(), 8080);
+        assert_eq!(
+            config.get_string("server.greeting").unwrap(),
+            "hello world!"
+        );
+        assert_eq!(config.get_string("server.name").unwrap(), "default");
+        assert!(config.get_string("server.missing").is_err());
+    }
+}
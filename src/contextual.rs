@@ -0,0 +1,129 @@
+use std::marker::PhantomData;
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome, ValueSource};
+
+/// Parser receiving a context along with the input, for parsing that depends on configuration
+/// `FromStr` has no way to pass in, e.g. a timezone or a registry of symbolic names.
+pub trait ContextualParser<T, Ctx> {
+    type Err: std::fmt::Debug;
+
+    fn parse(&self, input: &str, ctx: &Ctx) -> Result<T, Self::Err>;
+}
+
+impl<T, Ctx, E: std::fmt::Debug, F: Fn(&str, &Ctx) -> Result<T, E>> ContextualParser<T, Ctx> for F {
+    type Err = E;
+
+    fn parse(&self, input: &str, ctx: &Ctx) -> Result<T, E> {
+        self(input, ctx)
+    }
+}
+
+/// Source parsing the file contents with a [`ContextualParser`] and the context it holds.
+///
+/// The value is parsed from the cached file contents on each access, so changes to the
+/// context apply right away.
+pub struct ContextFileSource<T, Ctx, P, const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    parser: P,
+    ctx: Ctx,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T, Ctx, P: ContextualParser<T, Ctx>, const REQUIRED: bool>
+    ContextFileSource<T, Ctx, P, REQUIRED>
+{
+    pub fn new(source: FileSource<String, REQUIRED>, parser: P, ctx: Ctx) -> Self {
+        Self {
+            source,
+            parser,
+            ctx,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    pub fn ctx(&self) -> &Ctx {
+        &self.ctx
+    }
+
+    pub fn ctx_mut(&mut self) -> &mut Ctx {
+        &mut self.ctx
+    }
+
+    fn parsed(&self) -> Result<Option<T>, ValueError<P::Err>> {
+        let ValueOutcome::Loaded(contents) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            self.parser
+                .parse(&contents, &self.ctx)
+                .map_err(RefreshFileSourceError::ParseError)?,
+        ))
+    }
+}
+
+impl<T, Ctx, P: ContextualParser<T, Ctx>> ValueSource<T, P::Err>
+    for ContextFileSource<T, Ctx, P, true>
+{
+    fn value(&self) -> Result<T, ValueError<P::Err>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<T, Ctx, P: ContextualParser<T, Ctx>> ValueSource<Option<T>, P::Err>
+    for ContextFileSource<T, Ctx, P, false>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<P::Err>> {
+        self.parsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTC offset in minutes.
+    struct Timezone(i32);
+
+    /// Parses a local `HH:MM` time of day into minutes past midnight UTC.
+    struct UtcMinutes;
+
+    impl ContextualParser<i32, Timezone> for UtcMinutes {
+        type Err = String;
+
+        fn parse(&self, input: &str, ctx: &Timezone) -> Result<i32, String> {
+            let (hours, minutes) = input.split_once(':').ok_or("missing ':'")?;
+            let hours: i32 = hours.parse().map_err(|e| format!("{e}"))?;
+            let minutes: i32 = minutes.parse().map_err(|e| format!("{e}"))?;
+            Ok((hours * 60 + minutes - ctx.0).rem_euclid(24 * 60))
+        }
+    }
+
+    #[test]
+    fn timestamp_in_timezone() {
+        let mut source: ContextFileSource<_, _, _, true> = ContextFileSource::new(
+            FileSource::from_path("sources/test-local-time".into()),
+            UtcMinutes,
+            Timezone(120),
+        );
+        assert_eq!(source.value().unwrap(), 10 * 60 + 30);
+
+        source.ctx_mut().0 = -60;
+        assert_eq!(source.value().unwrap(), 13 * 60 + 30);
+    }
+
+    #[test]
+    fn closure_parser() {
+        let source: ContextFileSource<u16, u16, _, false> = ContextFileSource::new(
+            FileSource::from_path("sources/test-port".into()),
+            |input: &str, offset: &u16| input.parse::<u16>().map(|port| port + offset),
+            1,
+        );
+
+        assert_eq!(source.value().unwrap(), Some(8081));
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{Clock, RefreshFileSourceError, SystemClock, ValueError, refresh_due};
+
+/// Struct read from several keys of a [`DirSource`] at once, see [`DirSource::load`].
+/// Usually derived, with the `derive` feature.
+pub trait FromDir: Sized {
+    fn from_dir(files: &DirFiles<'_>) -> Result<Self, FromDirError>;
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FromDirError {
+    #[error("no file for required key {0:?}")]
+    MissingKey(String),
+    #[error("error parsing key {key:?}: {error}")]
+    ParseError { key: String, error: String },
+}
+
+/// Snapshot of the files of a [`DirSource`], passed to [`FromDir::from_dir`].
+pub struct DirFiles<'a>(&'a HashMap<String, String>);
+
+impl DirFiles<'_> {
+    /// Trimmed and parsed contents of the file `key`, `None` if there is none.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, FromDirError>
+    where
+        T::Err: std::fmt::Debug,
+    {
+        self.0
+            .get(key)
+            .map(|contents| contents.trim().parse())
+            .transpose()
+            .map_err(|e| FromDirError::ParseError {
+                key: key.to_owned(),
+                error: format!("{e:?}"),
+            })
+    }
+
+    /// Like [`DirFiles::get`], failing if there is no file `key`.
+    pub fn require<T: FromStr>(&self, key: &str) -> Result<T, FromDirError>
+    where
+        T::Err: std::fmt::Debug,
+    {
+        self.get(key)?
+            .ok_or_else(|| FromDirError::MissingKey(key.to_owned()))
+    }
+}
+
+/// Source reading every file of a directory, keyed by file name, e.g. a mounted ConfigMap or
+/// Secret volume.
+///
+/// Hidden entries are skipped, which covers the `..data` symlink and timestamped directories
+/// Kubernetes uses for atomic volume updates. Volumes with a `..data` symlink are read from the
+/// directory it points to, again if the symlink was swapped during the read, so values of
+/// different keys always come from the same update. Plain directories are read as they are. A
+/// missing directory has no keys.
+pub struct DirSource {
+    dir: PathBuf,
+    files: RwLock<Option<HashMap<String, String>>>,
+    refresh_interval: Option<Duration>,
+    last_refresh: RwLock<Option<Instant>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl DirSource {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            files: RwLock::new(None),
+            refresh_interval: None,
+            last_refresh: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Re-reads the directory, keeping the previous snapshot if any file fails to read.
+    pub fn refresh_value(&self) -> Result<(), RefreshFileSourceError<Infallible>> {
+        let data_link = self.dir.join("..data");
+        let files = loop {
+            let data = match std::fs::read_link(&data_link) {
+                Ok(data) => data,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break read_files(&self.dir)?,
+                Err(e) => return Err(e.into()),
+            };
+            // Reading from the resolved directory rather than through the per-key symlinks
+            // keeps a swap of `..data` between two keys from mixing files of two updates.
+            let files = read_files(&self.dir.join(&data));
+            // The kubelet removes the previous directory after swapping, failing the read.
+            if std::fs::read_link(&data_link).ok().as_ref() != Some(&data) {
+                continue;
+            }
+            break files?;
+        };
+
+        *self
+            .files
+            .write()
+            .expect("error locking files in DirSource") = Some(files);
+        *self
+            .last_refresh
+            .write()
+            .expect("error locking last_refresh in DirSource") = Some(self.clock.now());
+        Ok(())
+    }
+
+    fn needs_refresh(&self) -> bool {
+        let last_refresh = *self
+            .last_refresh
+            .read()
+            .expect("error reading last_refresh in DirSource");
+        refresh_due(last_refresh, self.refresh_interval, self.clock.now())
+    }
+
+    /// Refreshes on timeout and calls `f` with the current snapshot.
+    fn with_files<R, E: std::fmt::Debug>(
+        &self,
+        f: impl FnOnce(&HashMap<String, String>) -> R,
+    ) -> Result<R, RefreshFileSourceError<E>> {
+        if self.needs_refresh() {
+            self.refresh_value()
+                .map_err(RefreshFileSourceError::widen)?;
+        }
+
+        let files = self.files.read().expect("error reading files in DirSource");
+        Ok(f(files.as_ref().expect("refreshed at least once")))
+    }
+
+    /// Names of all files in the directory, sorted.
+    pub fn keys(&self) -> Result<Vec<String>, RefreshFileSourceError<Infallible>> {
+        let mut keys = self.with_files(|files| files.keys().cloned().collect::<Vec<_>>())?;
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    /// Trimmed and parsed contents of the file `key`, `None` if there is none.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, ValueError<T::Err>>
+    where
+        T::Err: std::fmt::Debug,
+    {
+        let contents = self.with_files(|files| files.get(key).cloned())?;
+        Ok(contents
+            .map(|contents| contents.trim().parse())
+            .transpose()
+            .map_err(RefreshFileSourceError::ParseError)?)
+    }
+
+    /// Reads several keys into `S` from a single snapshot, so for volumes updated through
+    /// `..data` its fields are consistent with each other, see [`DirSource`].
+    pub fn load<S: FromDir>(&self) -> Result<S, ValueError<FromDirError>> {
+        Ok(self
+            .with_files(|files| S::from_dir(&DirFiles(files)))?
+            .map_err(RefreshFileSourceError::ParseError)?)
+    }
+}
+
+/// Contents of the files in `dir`, keyed by name, skipping hidden entries. Empty if `dir`
+/// doesn't exist.
+fn read_files(dir: &Path) -> io::Result<HashMap<String, String>> {
+    let mut files = HashMap::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        // follows symlinks, e.g. the ones Kubernetes creates for each key
+        if name.starts_with('.') || !std::fs::metadata(entry.path())?.is_file() {
+            continue;
+        }
+        files.insert(name, std::fs::read_to_string(entry.path())?);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out `files` the way the kubelet does: in a timestamped directory, linked through
+    /// `..data`, with a symlink per key.
+    fn volume(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("..2026_10_14_12_00_00.000000001");
+        std::fs::create_dir(&data).unwrap();
+        for (name, contents) in files {
+            std::fs::write(data.join(name), contents).unwrap();
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::symlink;
+
+            symlink(&data, dir.path().join("..data")).unwrap();
+            for (name, _) in files {
+                symlink(format!("..data/{name}"), dir.path().join(name)).unwrap();
+            }
+        }
+        #[cfg(not(unix))]
+        for (name, contents) in files {
+            std::fs::write(dir.path().join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn keys_skip_hidden() {
+        let dir = volume(&[("host", "db.local"), ("port", "5432\n"), ("user", "admin")]);
+        let source = DirSource::new(dir.path().to_owned());
+
+        assert_eq!(source.keys().unwrap(), ["host", "port", "user"]);
+        assert_eq!(source.get::<u16>("port").unwrap(), Some(5432));
+        assert_eq!(source.get::<String>("password").unwrap(), None);
+    }
+
+    #[cfg(feature = "derive")]
+    #[derive(Debug, PartialEq, crate::FromDir)]
+    struct Database {
+        host: String,
+        #[from_dir(key = "port")]
+        db_port: u16,
+        password: Option<String>,
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn load_struct() {
+        let dir = volume(&[("host", "db.local"), ("port", "5432\n")]);
+        let source = DirSource::new(dir.path().to_owned());
+
+        assert_eq!(
+            source.load::<Database>().unwrap(),
+            Database {
+                host: "db.local".to_owned(),
+                db_port: 5432,
+                password: None,
+            }
+        );
+
+        let dir = volume(&[("host", "db.local")]);
+        let source = DirSource::new(dir.path().to_owned());
+        assert!(matches!(
+            source.load::<Database>(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(FromDirError::MissingKey(key))
+            )) if key == "port"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_through_data_link() {
+        let dir = volume(&[("host", "db.local"), ("port", "5432")]);
+        let update = dir.path().join("..2026_10_14_12_05_00.000000001");
+        std::fs::create_dir(&update).unwrap();
+        std::fs::write(update.join("host"), "db2.local").unwrap();
+        std::fs::write(update.join("port"), "6432").unwrap();
+        std::fs::write(update.join("user"), "admin").unwrap();
+        // The kubelet swaps `..data` before it links newly added keys.
+        std::fs::remove_file(dir.path().join("..data")).unwrap();
+        std::os::unix::fs::symlink(&update, dir.path().join("..data")).unwrap();
+        let source = DirSource::new(dir.path().to_owned());
+
+        assert_eq!(source.keys().unwrap(), ["host", "port", "user"]);
+        assert_eq!(source.get::<u16>("port").unwrap(), Some(6432));
+    }
+
+    #[test]
+    fn missing_dir_has_no_keys() {
+        let source = DirSource::new("sources/test-dir-missing".into());
+
+        assert!(source.keys().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DockerConfigError {
+    #[error("invalid docker config JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("auth of registry {0:?} is not valid base64: {1}")]
+    Base64(String, base64::DecodeError),
+    #[error("auth of registry {0:?} is not of the form username:password")]
+    InvalidAuth(String),
+}
+
+#[derive(Deserialize)]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, RegistryAuth>,
+}
+
+#[derive(Deserialize)]
+struct RegistryAuth {
+    auth: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+/// Source reading an image pull secret's `.dockerconfigjson`, decoding the per-registry
+/// credentials. Parsed from the cached file contents on each access.
+pub struct DockerConfigSource<const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+}
+
+impl<const REQUIRED: bool> DockerConfigSource<REQUIRED> {
+    pub fn new(source: FileSource<String, REQUIRED>) -> Self {
+        Self { source }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    /// `(username, password)` for `registry`, matching entries with or without a URL scheme
+    /// and path (e.g. `https://index.docker.io/v1/` for `index.docker.io`).
+    pub fn credentials_for(
+        &self,
+        registry: &str,
+    ) -> Result<Option<(String, String)>, ValueError<DockerConfigError>> {
+        let contents = match self.source.refreshed_raw()? {
+            ValueOutcome::Loaded(contents) => contents,
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing if REQUIRED => {
+                return Err(ValueError::NoValue);
+            }
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => return Ok(None),
+        };
+        let config: DockerConfig = serde_json::from_str(&contents)
+            .map_err(|e| RefreshFileSourceError::ParseError(e.into()))?;
+
+        let Some((key, auth)) = config
+            .auths
+            .into_iter()
+            .find(|(key, _)| key == registry || registry_host(key) == registry)
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            decode(&key, auth).map_err(RefreshFileSourceError::ParseError)?,
+        ))
+    }
+}
+
+fn registry_host(key: &str) -> &str {
+    let key = key
+        .strip_prefix("https://")
+        .or_else(|| key.strip_prefix("http://"))
+        .unwrap_or(key);
+    key.split('/').next().unwrap_or(key)
+}
+
+fn decode(registry: &str, auth: RegistryAuth) -> Result<(String, String), DockerConfigError> {
+    let Some(encoded) = auth.auth else {
+        return auth
+            .username
+            .zip(auth.password)
+            .ok_or_else(|| DockerConfigError::InvalidAuth(registry.to_owned()));
+    };
+
+    let decoded = STANDARD
+        .decode(encoded)
+        .map_err(|e| DockerConfigError::Base64(registry.to_owned(), e))?;
+    let decoded = String::from_utf8(decoded)
+        .map_err(|_| DockerConfigError::InvalidAuth(registry.to_owned()))?;
+    decoded
+        .split_once(':')
+        .map(|(username, password)| (username.to_owned(), password.to_owned()))
+        .ok_or_else(|| DockerConfigError::InvalidAuth(registry.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_for_registry() {
+        let source = DockerConfigSource::<true>::new(FileSource::from_path(
+            "sources/test-dockerconfigjson".into(),
+        ));
+
+        assert_eq!(
+            source.credentials_for("registry.example.com").unwrap(),
+            Some(("robot".to_owned(), "s3cr:et".to_owned()))
+        );
+        assert_eq!(
+            source.credentials_for("index.docker.io").unwrap(),
+            Some(("user".to_owned(), "pass".to_owned()))
+        );
+        assert_eq!(source.credentials_for("ghcr.io").unwrap(), None);
+    }
+}
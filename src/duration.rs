@@ -0,0 +1,64 @@
+use std::{str::FromStr, time::Duration};
+
+/// Duration parsed from an integer with an optional unit suffix (`ms`, `s`, `m`, `h`);
+/// bare numbers are seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DurationValue(pub Duration);
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid duration: {0:?}")]
+pub struct DurationParseError(String);
+
+impl FromStr for DurationValue {
+    type Err = DurationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, unit) = s.split_at(split);
+        let amount: u64 = amount
+            .parse()
+            .map_err(|_| DurationParseError(s.to_owned()))?;
+        let duration = match unit.trim() {
+            "ms" => Some(Duration::from_millis(amount)),
+            "" | "s" => Some(Duration::from_secs(amount)),
+            "m" => amount.checked_mul(60).map(Duration::from_secs),
+            "h" => amount.checked_mul(60 * 60).map(Duration::from_secs),
+            _ => None,
+        };
+
+        duration
+            .map(Self)
+            .ok_or_else(|| DurationParseError(s.to_owned()))
+    }
+}
+
+impl From<DurationValue> for Duration {
+    fn from(value: DurationValue) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_units() {
+        assert_eq!("30".parse(), Ok(DurationValue(Duration::from_secs(30))));
+        assert_eq!("30s".parse(), Ok(DurationValue(Duration::from_secs(30))));
+        assert_eq!(
+            "500ms".parse(),
+            Ok(DurationValue(Duration::from_millis(500)))
+        );
+        assert_eq!("2m".parse(), Ok(DurationValue(Duration::from_secs(120))));
+        assert_eq!("1h".parse(), Ok(DurationValue(Duration::from_secs(3600))));
+        assert!("1d".parse::<DurationValue>().is_err());
+        assert!("s".parse::<DurationValue>().is_err());
+    }
+
+    #[test]
+    fn overflow_is_error() {
+        assert!("307445734561825861m".parse::<DurationValue>().is_err());
+        assert!(format!("{}h", u64::MAX).parse::<DurationValue>().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use std::{borrow::Cow, str::FromStr};
+
+use encoding_rs::Encoding;
+
+use crate::{FileSource, RefreshFileSourceError, decode_utf8};
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Decode files with `encoding`, e.g. [`encoding_rs::WINDOWS_1252`] for Latin-1, instead of
+    /// strict UTF-8. Malformed input fails the refresh with
+    /// [`RefreshFileSourceError::DecodeError`].
+    pub fn set_encoding(&mut self, encoding: Option<&'static Encoding>) -> &mut Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Decodes `bytes` with the configured encoding, as strict UTF-8 without one.
+    pub(crate) fn decode<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> Result<Cow<'a, str>, RefreshFileSourceError<E>> {
+        let Some(encoding) = self.encoding else {
+            return Ok(Cow::Borrowed(decode_utf8(bytes)?));
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .ok_or(RefreshFileSourceError::DecodeError(encoding.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestSource, ValueError, ValueSource};
+
+    #[test]
+    fn latin1() {
+        let mut source: TestSource<String, true> = TestSource::new(b"caf\xe9\n");
+        source.set_encoding(Some(encoding_rs::WINDOWS_1252));
+
+        assert_eq!(source.value().unwrap(), "café");
+
+        let utf8: FileSource<String, true> = FileSource::from_path(source.path().to_owned());
+        assert!(matches!(
+            utf8.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::IOError(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn malformed_utf16() {
+        let mut source: TestSource<u16, true> = TestSource::new(b"\xff\xfe8\x000\x00\x00\xd8");
+        source.set_encoding(Some(encoding_rs::UTF_16LE));
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::DecodeError("UTF-16LE")
+            ))
+        ));
+
+        source.write(b"\xff\xfe8\x000\x008\x000\x00");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+}
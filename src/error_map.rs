@@ -0,0 +1,136 @@
+use std::{path::PathBuf, str::FromStr};
+
+use crate::{
+    FileSource, RefreshFileSourceError, Refreshable, ValueError, ValueOutcome, ValueSource,
+};
+
+/// What is known about a failed parse, handed to the error map of an [`ErrorMappedSource`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ParseContext<E> {
+    pub path: PathBuf,
+    /// The (trimmed) file contents, `None` if redacted with
+    /// [`ErrorMappedSource::set_redact_input`].
+    pub input: Option<String>,
+    pub error: E,
+}
+
+pub type ErrorMap<E, E2> = Box<dyn Fn(ParseContext<E>) -> E2 + Send + Sync>;
+
+/// Source mapping parse errors into a domain error type `E2`, e.g. to attach the config key and
+/// a remediation hint. Parses from the cached file contents on each access.
+pub struct ErrorMappedSource<T: FromStr, E2, const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    error_map: ErrorMap<T::Err, E2>,
+    redact_input: bool,
+}
+
+impl<T: FromStr, E2: std::fmt::Debug, const REQUIRED: bool> ErrorMappedSource<T, E2, REQUIRED> {
+    pub fn new(source: FileSource<String, REQUIRED>, error_map: ErrorMap<T::Err, E2>) -> Self {
+        Self {
+            source,
+            error_map,
+            redact_input: false,
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    /// Leave the file contents out of the [`ParseContext`], for sources holding secrets.
+    pub fn set_redact_input(&mut self, redact_input: bool) -> &mut Self {
+        self.redact_input = redact_input;
+        self
+    }
+
+    fn parsed(&self) -> Result<Option<T>, ValueError<E2>> {
+        let ValueOutcome::Loaded(input) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+        input.parse().map(Some).map_err(|error| {
+            let context = ParseContext {
+                path: Refreshable::path(&self.source),
+                input: (!self.redact_input).then(|| input.clone()),
+                error,
+            };
+            RefreshFileSourceError::ParseError((self.error_map)(context)).into()
+        })
+    }
+}
+
+impl<T: FromStr, E2: std::fmt::Debug> ValueSource<T, E2> for ErrorMappedSource<T, E2, true> {
+    fn value(&self) -> Result<T, ValueError<E2>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<T: FromStr, E2: std::fmt::Debug> ValueSource<Option<T>, E2>
+    for ErrorMappedSource<T, E2, false>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<E2>> {
+        self.parsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ConfigError {
+        file: String,
+        input: Option<String>,
+        hint: &'static str,
+    }
+
+    fn port_error(context: ParseContext<ParseIntError>) -> ConfigError {
+        ConfigError {
+            file: context
+                .path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned(),
+            input: context.input,
+            hint: "expected a port number",
+        }
+    }
+
+    #[test]
+    fn maps_parse_error() {
+        let mut source = ErrorMappedSource::<u16, _, true>::new(
+            FileSource::from_path("sources/test-required".into()),
+            Box::new(port_error),
+        );
+
+        let Err(ValueError::RefreshFileSourceError(RefreshFileSourceError::ParseError(error))) =
+            source.value()
+        else {
+            panic!("expected a mapped parse error");
+        };
+        assert_eq!(error.file, "test-required");
+        assert_eq!(error.input.as_deref(), Some("hello world!"));
+        assert_eq!(error.hint, "expected a port number");
+
+        source.set_redact_input(true);
+        let Err(ValueError::RefreshFileSourceError(RefreshFileSourceError::ParseError(error))) =
+            source.value()
+        else {
+            panic!("expected a mapped parse error");
+        };
+        assert_eq!(error.input, None);
+    }
+
+    #[test]
+    fn parses_valid_value() {
+        let source = ErrorMappedSource::<u16, _, false>::new(
+            FileSource::from_path("sources/test-port".into()),
+            Box::new(port_error),
+        );
+
+        assert_eq!(source.value().unwrap(), Some(8080));
+    }
+}
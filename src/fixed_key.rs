@@ -0,0 +1,241 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Clock, Location, RefreshFileSourceError, SecretBackend, SystemClock, ValueError, ValueSource,
+    backend::{default_backend, read_entry},
+    refresh_due, retry_throttled,
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FixedKeyError {
+    #[error("key must be exactly {expected} bytes, got {got}")]
+    WrongLength { expected: usize, got: usize },
+    #[cfg(feature = "base64")]
+    #[error("key is not valid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+}
+
+/// Required source for key material of exactly `N` bytes, read as raw bytes rather than text.
+/// The key is never printed.
+pub struct FixedKeySource<const N: usize> {
+    path: PathBuf,
+    #[cfg(feature = "base64")]
+    base64: bool,
+    value: RwLock<Option<[u8; N]>>,
+    refresh_interval: Option<Duration>,
+    last_refresh: RwLock<Option<Instant>>,
+    min_retry_interval: Option<Duration>,
+    last_failed_refresh: RwLock<Option<Instant>>,
+    clock: Arc<dyn Clock>,
+    backend: Arc<dyn SecretBackend>,
+}
+
+impl<const N: usize> FixedKeySource<N> {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            #[cfg(feature = "base64")]
+            base64: false,
+            value: RwLock::new(None),
+            refresh_interval: None,
+            last_refresh: RwLock::new(None),
+            min_retry_interval: None,
+            last_failed_refresh: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+            backend: default_backend(),
+        }
+    }
+
+    /// Decode the file as base64 (ignoring surrounding whitespace) before checking the length.
+    #[cfg(feature = "base64")]
+    pub fn set_base64(&mut self, base64: bool) -> &mut Self {
+        self.base64 = base64;
+        self
+    }
+
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Minimum time between refresh attempts after a failed one, like
+    /// [`FileSource::set_min_retry_interval`](crate::FileSource::set_min_retry_interval).
+    pub fn set_min_retry_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.min_retry_interval = interval;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Read through `backend` instead of the default [`crate::FsBackend`], like
+    /// [`FileSource::set_backend`](crate::FileSource::set_backend).
+    pub fn set_backend(&mut self, backend: Option<Arc<dyn SecretBackend>>) -> &mut Self {
+        self.backend = backend.unwrap_or_else(default_backend);
+        self
+    }
+
+    pub fn refresh_value(&self) -> Result<(), RefreshFileSourceError<FixedKeyError>> {
+        let Some(read) = read_entry(&*self.backend, &Location::Path(self.path.clone()))? else {
+            return Err(RefreshFileSourceError::NoValue);
+        };
+        let bytes = read.contents;
+        #[cfg(feature = "base64")]
+        let bytes = if self.base64 {
+            use base64::{Engine, engine::general_purpose::STANDARD};
+
+            STANDARD
+                .decode(bytes.trim_ascii())
+                .map_err(|e| RefreshFileSourceError::ParseError(e.into()))?
+        } else {
+            bytes
+        };
+
+        let key = <[u8; N]>::try_from(bytes.as_slice()).map_err(|_| {
+            RefreshFileSourceError::ParseError(FixedKeyError::WrongLength {
+                expected: N,
+                got: bytes.len(),
+            })
+        })?;
+        *self
+            .value
+            .write()
+            .expect("error locking value in FixedKeySource") = Some(key);
+        *self
+            .last_refresh
+            .write()
+            .expect("error locking last_refresh in FixedKeySource") = Some(self.clock.now());
+        Ok(())
+    }
+
+    fn needs_refresh(&self) -> bool {
+        let last_refresh = *self
+            .last_refresh
+            .read()
+            .expect("error reading last_refresh in FixedKeySource");
+        refresh_due(last_refresh, self.refresh_interval, self.clock.now())
+    }
+
+    /// Whether the last refresh failed less than the minimum retry interval ago.
+    fn retry_throttled(&self) -> bool {
+        let last_failed_refresh = *self
+            .last_failed_refresh
+            .read()
+            .expect("error reading last_failed_refresh in FixedKeySource");
+        retry_throttled(
+            last_failed_refresh,
+            self.min_retry_interval,
+            self.clock.now(),
+        )
+    }
+
+    fn record_refresh_attempt(
+        &self,
+        result: Result<(), RefreshFileSourceError<FixedKeyError>>,
+    ) -> Result<(), RefreshFileSourceError<FixedKeyError>> {
+        if self.min_retry_interval.is_some() {
+            *self
+                .last_failed_refresh
+                .write()
+                .expect("error locking last_failed_refresh in FixedKeySource") =
+                result.is_err().then(|| self.clock.now());
+        }
+        result
+    }
+}
+
+impl<const N: usize> ValueSource<[u8; N], FixedKeyError> for FixedKeySource<N> {
+    fn value(&self) -> Result<[u8; N], ValueError<FixedKeyError>> {
+        if self.needs_refresh() && !self.retry_throttled() {
+            self.record_refresh_attempt(self.refresh_value())?;
+        }
+
+        self.value
+            .read()
+            .expect("error reading value in FixedKeySource")
+            .ok_or(ValueError::NoValue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn exact_length() {
+        let source = FixedKeySource::<8>::new("sources/test-key-raw".into());
+
+        assert_eq!(&source.value().unwrap(), b"01234567");
+    }
+
+    #[test]
+    fn wrong_length() {
+        let source = FixedKeySource::<32>::new("sources/test-key-raw".into());
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(FixedKeyError::WrongLength {
+                    expected: 32,
+                    got: 8
+                })
+            ))
+        ));
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64_decoded() {
+        let mut source = FixedKeySource::<8>::new("sources/test-key-base64".into());
+        source.set_base64(true);
+
+        assert_eq!(&source.value().unwrap(), b"01234567");
+    }
+
+    #[test]
+    fn failed_refresh_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key");
+        let clock = MockClock::new();
+        let mut source = FixedKeySource::<8>::new(path.clone());
+        source
+            .set_min_retry_interval(Some(Duration::from_secs(10)))
+            .set_clock(Arc::new(clock.clone()));
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::NoValue
+            ))
+        ));
+
+        std::fs::write(&path, "01234567").unwrap();
+        assert!(matches!(source.value(), Err(ValueError::NoValue)));
+        clock.advance(Duration::from_secs(11));
+        assert_eq!(&source.value().unwrap(), b"01234567");
+    }
+
+    #[test]
+    fn reads_through_backend() {
+        struct KeyBackend;
+
+        impl SecretBackend for KeyBackend {
+            fn fetch(&self, _: &str) -> io::Result<Option<Vec<u8>>> {
+                Ok(Some(vec![0, 1, 2, 3]))
+            }
+        }
+
+        let mut source = FixedKeySource::<4>::new("tls/key".into());
+        source.set_backend(Some(Arc::new(KeyBackend)));
+
+        assert_eq!(source.value().unwrap(), [0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::Refreshable;
+
+/// Set of sources refreshed together, reporting every failure rather than just the first.
+#[derive(Default)]
+pub struct RefreshGroup {
+    sources: Vec<Arc<dyn Refreshable>>,
+    on_panic: Option<PanicCallback>,
+}
+
+pub type RefreshGroupError = Vec<(String, Box<dyn Error + Send + Sync>)>;
+
+/// Called with the path of a source that panicked while refreshing on the refresher thread.
+pub type PanicCallback = Box<dyn Fn(&Path) + Send + Sync>;
+
+impl RefreshGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, source: Arc<dyn Refreshable>) -> &mut Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Reports sources panicking on the thread of [`RefreshGroup::spawn_refresher`].
+    pub fn set_on_panic(&mut self, on_panic: PanicCallback) -> &mut Self {
+        self.on_panic = Some(on_panic);
+        self
+    }
+
+    /// Refreshes all sources, collecting `(path, error)` pairs for each one that failed.
+    pub fn refresh_all(&self) -> Result<(), RefreshGroupError> {
+        let errors: RefreshGroupError = self
+            .sources
+            .iter()
+            .filter_map(|source| {
+                source
+                    .refresh()
+                    .err()
+                    .map(|e| (source.path().display().to_string(), e))
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Refreshes all sources on a background thread every `interval` until the returned
+    /// [`Refresher`] is stopped or dropped. A source panicking while refreshing, e.g. from its
+    /// change callback, is recorded in [`Refresher::panicked_sources`] and reported to the
+    /// callback set with [`RefreshGroup::set_on_panic`], without stopping the other sources
+    /// from refreshing.
+    pub fn spawn_refresher(self, interval: Duration) -> Refresher {
+        let stop = Arc::new(AtomicBool::new(false));
+        let panicked = Arc::new(Mutex::new(BTreeSet::new()));
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            let panicked = panicked.clone();
+            move || {
+                while !stop.load(Ordering::Acquire) {
+                    for source in &self.sources {
+                        // Refresh errors stay visible through the sources themselves.
+                        if panic::catch_unwind(AssertUnwindSafe(|| source.refresh())).is_err() {
+                            let path = source.path();
+                            if let Some(on_panic) = &self.on_panic {
+                                on_panic(&path);
+                            }
+                            panicked
+                                .lock()
+                                .expect("error locking panicked sources in Refresher")
+                                .insert(path);
+                        }
+                    }
+                    thread::park_timeout(interval);
+                }
+            }
+        });
+
+        Refresher {
+            stop,
+            panicked,
+            handle: Some(handle),
+        }
+    }
+
+    /// Consistent multi-value reads across all sources of the group.
+    pub fn view(&self) -> ConsistentView<'_> {
+        ConsistentView {
+            sources: &self.sources,
+        }
+    }
+}
+
+/// Handle to the background thread of [`RefreshGroup::spawn_refresher`], stopping it when
+/// dropped.
+pub struct Refresher {
+    stop: Arc<AtomicBool>,
+    panicked: Arc<Mutex<BTreeSet<PathBuf>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Refresher {
+    /// Paths of sources that panicked while refreshing at least once.
+    pub fn panicked_sources(&self) -> Vec<PathBuf> {
+        self.panicked
+            .lock()
+            .expect("error locking panicked sources in Refresher")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Stops the refresh thread and waits for its current round to finish.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Refresher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Seqlock-style reader over a group: a read is only accepted if no source of the group stored a
+/// new value while it ran, otherwise it is retried.
+pub struct ConsistentView<'a> {
+    sources: &'a [Arc<dyn Refreshable>],
+}
+
+impl ConsistentView<'_> {
+    /// Current generation of every source, in the order they were added to the group.
+    pub fn generations(&self) -> Vec<u64> {
+        self.sources
+            .iter()
+            .map(|source| source.generation())
+            .collect()
+    }
+
+    /// Runs `read` until it completes without any source changing in between and returns its
+    /// result. Reads that trigger a refresh themselves, e.g. once the refresh interval elapsed,
+    /// cause one extra attempt if the refresh loads a changed value.
+    pub fn read<R>(&self, mut read: impl FnMut() -> R) -> R {
+        loop {
+            let before = self.generations();
+            let result = read();
+            if self.generations() == before {
+                return result;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileSource, TestSource, ValueOutcome, ValueSource};
+
+    #[test]
+    fn reports_all_failures() {
+        let mut group = RefreshGroup::new();
+        group
+            .add(Arc::new(FileSource::<String, true>::from_path(
+                "sources/test-required".into(),
+            )))
+            .add(Arc::new(FileSource::<String, true>::from_path(
+                "sources/test-group-missing".into(),
+            )))
+            .add(Arc::new(FileSource::<u16, true>::from_path(
+                "sources/test-required".into(),
+            )));
+
+        let errors = group.refresh_all().unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["sources/test-group-missing", "sources/test-required"]
+        );
+    }
+
+    #[test]
+    fn refresher_survives_panicking_source() {
+        let mut panicking: TestSource<String, true> = TestSource::new("first");
+        panicking.set_on_change(Box::new(|_| panic!("callback failed")));
+        panicking.refresh_value().unwrap();
+        let panicking = Arc::new(panicking);
+        let panicking_path = panicking.path().to_owned();
+        let healthy: Arc<TestSource<String, true>> = Arc::new(TestSource::new("first"));
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let mut group = RefreshGroup::new();
+        let on_panic_reported = reported.clone();
+        group
+            .add(panicking.clone())
+            .add(healthy.clone())
+            .set_on_panic(Box::new(move |path| {
+                on_panic_reported.lock().unwrap().push(path.to_owned())
+            }));
+        panicking.write("second");
+        let refresher = group.spawn_refresher(Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(50));
+        healthy.write("second");
+
+        let mut attempts = 0;
+        while healthy.cached() != ValueOutcome::Loaded("second".to_owned()) {
+            attempts += 1;
+            assert!(attempts < 200, "healthy source stopped refreshing");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            refresher.panicked_sources(),
+            std::slice::from_ref(&panicking_path)
+        );
+        refresher.stop();
+        assert!(
+            reported
+                .lock()
+                .unwrap()
+                .iter()
+                .all(|path| *path == panicking_path)
+        );
+        assert!(!reported.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn view_retries_on_concurrent_refresh() {
+        let host: Arc<TestSource<String, true>> = Arc::new(TestSource::new("old-host"));
+        let port: Arc<TestSource<u16, true>> = Arc::new(TestSource::new("1"));
+        host.refresh_value().unwrap();
+        port.refresh_value().unwrap();
+
+        let mut group = RefreshGroup::new();
+        group.add(host.clone()).add(port.clone());
+        let view = group.view();
+
+        let mut attempts = 0;
+        let (read_host, read_port) = view.read(|| {
+            attempts += 1;
+            let read_host = host.value().unwrap();
+            if attempts == 1 {
+                // Simulates a background refresher updating both files between the two reads.
+                host.write("new-host");
+                port.write("2");
+                host.refresh_value().unwrap();
+                port.refresh_value().unwrap();
+            }
+            (read_host, port.value().unwrap())
+        });
+
+        assert_eq!(attempts, 2);
+        assert_eq!(read_host, "new-host");
+        assert_eq!(read_port, 2);
+        assert_eq!(view.generations(), [2, 2]);
+    }
+
+    #[test]
+    fn unchanged_refresh_does_not_retry() {
+        let source = Arc::new(FileSource::<u16, true>::from_path(
+            "sources/test-port".into(),
+        ));
+        source.refresh_value().unwrap();
+        let generation = source.generation();
+        let mut group = RefreshGroup::new();
+        group.add(source.clone());
+        let view = group.view();
+
+        let mut attempts = 0;
+        let port = view.read(|| {
+            attempts += 1;
+            source.refresh_value().unwrap();
+            source.value().unwrap()
+        });
+
+        assert_eq!((attempts, port), (1, 8080));
+        assert_eq!(source.generation(), generation);
+    }
+}
@@ -0,0 +1,52 @@
+use std::{borrow::Cow, io::Read, str::FromStr};
+
+use flate2::read::GzDecoder;
+
+use crate::FileSource;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Check each file for the gzip magic bytes and decompress it if present, reading it as
+    /// plain text otherwise. Decompression happens before decoding with
+    /// [`FileSource::set_encoding`].
+    pub fn set_sniff_gzip(&mut self, sniff_gzip: bool) -> &mut Self {
+        self.sniff_gzip = sniff_gzip;
+        self
+    }
+
+    /// Decompresses `bytes` if sniffing is enabled and they start with the gzip magic bytes.
+    pub(crate) fn gunzip_sniffed<'a>(&self, bytes: &'a [u8]) -> std::io::Result<Cow<'a, [u8]>> {
+        if !self.sniff_gzip || !bytes.starts_with(&GZIP_MAGIC) {
+            return Ok(Cow::Borrowed(bytes));
+        }
+        let mut contents = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut contents)?;
+        Ok(Cow::Owned(contents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::GzEncoder};
+
+    use crate::{FileSource, TestSource, ValueSource};
+
+    #[test]
+    fn gzipped_and_plain() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"8080\n").unwrap();
+
+        let mut gzipped: TestSource<u16, true> = TestSource::new(encoder.finish().unwrap());
+        gzipped.set_sniff_gzip(true);
+        assert_eq!(gzipped.value().unwrap(), 8080);
+
+        let mut plain: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        plain.set_sniff_gzip(true);
+        assert_eq!(plain.value().unwrap(), 8080);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{collections::VecDeque, str::FromStr, time::Instant};
+
+use crate::FileSource;
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Recently loaded values with the time they were loaded, oldest first.
+    pub fn history(&self) -> Vec<(Instant, T)> {
+        self.history
+            .lock()
+            .expect("error locking history in FileSource")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn record_history(&self, value: &T) {
+        let mut history = self
+            .history
+            .lock()
+            .expect("error locking history in FileSource");
+        if history.len() == self.history_capacity {
+            history.pop_front();
+        }
+        history.push_back((self.clock.now(), value.clone()));
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone + PartialEq, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Keep the last `capacity` changed values loaded, for diagnosing flapping config; `0`
+    /// disables the history. Values are compared by `PartialEq` unless a change comparator is
+    /// set. Clears the current history.
+    pub fn set_history_capacity(&mut self, capacity: usize) -> &mut Self
+    where
+        T: 'static,
+    {
+        self.history_capacity = capacity;
+        *self
+            .history
+            .get_mut()
+            .expect("error locking history in FileSource") = VecDeque::with_capacity(capacity);
+        self.change_comparator
+            .get_or_insert_with(|| Box::new(|old: &T, new: &T| old != new));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Clock, TestSource};
+
+    #[test]
+    fn records_changes_in_order() {
+        let mut source: TestSource<u16, true> = TestSource::missing();
+        source.set_history_capacity(3);
+
+        let start = source.clock().now();
+        for value in ["1", "1", "2", "3"] {
+            source.write(value);
+            source.refresh_value().unwrap();
+            source.advance(Duration::from_secs(1));
+        }
+        assert_eq!(
+            source.history(),
+            [
+                (start, 1),
+                (start + Duration::from_secs(2), 2),
+                (start + Duration::from_secs(3), 3),
+            ]
+        );
+
+        source.write("4");
+        source.refresh_value().unwrap();
+        let values: Vec<u16> = source.history().into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, [2, 3, 4]);
+    }
+}
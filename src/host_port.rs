@@ -0,0 +1,131 @@
+use std::{fmt, str::FromStr};
+
+use crate::FileSource;
+
+/// `host:port` pair split on the last colon, without resolving the host. IPv6 addresses must be
+/// bracketed (`[::1]:8080`) and are stored without the brackets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostPort {
+    pub host: String,
+    pub port: u16,
+}
+
+pub type HostPortSource<const REQUIRED: bool> = FileSource<HostPort, REQUIRED>;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum HostPortParseError {
+    #[error("missing port in {0:?}")]
+    MissingPort(String),
+    #[error("missing host in {0:?}")]
+    MissingHost(String),
+    #[error("invalid port in {0:?}, expected 1-65535")]
+    InvalidPort(String),
+}
+
+impl FromStr for HostPort {
+    type Err = HostPortParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, port) = s
+            .rsplit_once(':')
+            .ok_or_else(|| HostPortParseError::MissingPort(s.to_owned()))?;
+        let host = match host.strip_prefix('[') {
+            Some(bracketed) => bracketed
+                .strip_suffix(']')
+                .ok_or_else(|| HostPortParseError::MissingHost(s.to_owned()))?,
+            // An unbracketed IPv6 address would be split in the middle.
+            None if host.contains(':') => {
+                return Err(HostPortParseError::MissingPort(s.to_owned()));
+            }
+            None => host,
+        };
+        if host.is_empty() {
+            return Err(HostPortParseError::MissingHost(s.to_owned()));
+        }
+        if port.is_empty() {
+            return Err(HostPortParseError::MissingPort(s.to_owned()));
+        }
+        let port = port
+            .parse()
+            .ok()
+            .filter(|&port| port != 0)
+            .ok_or_else(|| HostPortParseError::InvalidPort(s.to_owned()))?;
+
+        Ok(Self {
+            host: host.to_owned(),
+            port,
+        })
+    }
+}
+
+impl fmt::Display for HostPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl From<HostPort> for (String, u16) {
+    fn from(value: HostPort) -> Self {
+        (value.host, value.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValueSource;
+
+    #[test]
+    fn parse_hostname() {
+        assert_eq!(
+            "db.svc:5432".parse(),
+            Ok(HostPort {
+                host: "db.svc".to_owned(),
+                port: 5432
+            })
+        );
+
+        let source: HostPortSource<true> = FileSource::from_path("sources/test-host-port".into());
+        assert_eq!(
+            <(String, u16)>::from(source.value().unwrap()),
+            ("db.svc.cluster.local".to_owned(), 5432)
+        );
+    }
+
+    #[test]
+    fn parse_bracketed_ipv6() {
+        let host_port: HostPort = "[::1]:8080".parse().unwrap();
+        assert_eq!(host_port.host, "::1");
+        assert_eq!(host_port.port, 8080);
+        assert_eq!(host_port.to_string(), "[::1]:8080");
+        assert!("::1:8080".parse::<HostPort>().is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "db.svc".parse::<HostPort>(),
+            Err(HostPortParseError::MissingPort("db.svc".to_owned()))
+        );
+        assert_eq!(
+            "db.svc:".parse::<HostPort>(),
+            Err(HostPortParseError::MissingPort("db.svc:".to_owned()))
+        );
+        assert_eq!(
+            ":5432".parse::<HostPort>(),
+            Err(HostPortParseError::MissingHost(":5432".to_owned()))
+        );
+        assert_eq!(
+            "db.svc:0".parse::<HostPort>(),
+            Err(HostPortParseError::InvalidPort("db.svc:0".to_owned()))
+        );
+        assert_eq!(
+            "db.svc:65536".parse::<HostPort>(),
+            Err(HostPortParseError::InvalidPort("db.svc:65536".to_owned()))
+        );
+    }
+}
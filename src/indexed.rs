@@ -0,0 +1,153 @@
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Clock, RefreshFileSourceError, SystemClock, ValueError, ValueSource, read_once, refresh_due,
+};
+
+/// Source reading a numbered series of files (`shard-0`, `shard-1`, …) from a directory into a
+/// `Vec` ordered by index. The directory is re-scanned on every refresh, picking up added and
+/// removed files; gaps in the numbering are skipped.
+pub struct IndexedSource<T: FromStr + Clone>
+where
+    T::Err: std::fmt::Debug,
+{
+    dir: PathBuf,
+    prefix: String,
+    values: RwLock<Option<Vec<T>>>,
+    refresh_interval: Option<Duration>,
+    last_refresh: RwLock<Option<Instant>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> IndexedSource<T> {
+    /// Reads the files named `{prefix}-{index}` in `dir`.
+    pub fn new(dir: PathBuf, prefix: impl Into<String>) -> Self {
+        Self {
+            dir,
+            prefix: prefix.into(),
+            values: RwLock::new(None),
+            refresh_interval: None,
+            last_refresh: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Re-scans the directory and reads every indexed file, keeping the previous values if
+    /// any file fails to read or parse.
+    pub fn refresh_value(&self) -> Result<(), RefreshFileSourceError<E>> {
+        let mut indexed = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let index = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(&self.prefix))
+                .and_then(|name| name.strip_prefix('-'))
+                .and_then(|index| index.parse::<usize>().ok());
+            if let Some(index) = index {
+                indexed.push((index, entry.path()));
+            }
+        }
+        indexed.sort_unstable_by_key(|(index, _)| *index);
+
+        let values = indexed
+            .into_iter()
+            .map(|(_, path)| {
+                // A shard removed since the scan fails the refresh like any other read
+                read_once::<T>(path, false, true)?.ok_or(RefreshFileSourceError::NoValue)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        *self
+            .values
+            .write()
+            .expect("error locking values in IndexedSource") = Some(values);
+        *self
+            .last_refresh
+            .write()
+            .expect("error locking last_refresh in IndexedSource") = Some(self.clock.now());
+        Ok(())
+    }
+
+    fn needs_refresh(&self) -> bool {
+        let last_refresh = *self
+            .last_refresh
+            .read()
+            .expect("error reading last_refresh in IndexedSource");
+        refresh_due(last_refresh, self.refresh_interval, self.clock.now())
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<Vec<T>, E> for IndexedSource<T> {
+    fn value(&self) -> Result<Vec<T>, ValueError<E>> {
+        if self.needs_refresh() {
+            self.refresh_value()?;
+        }
+
+        self.values
+            .read()
+            .expect("error reading values in IndexedSource")
+            .clone()
+            .ok_or(ValueError::NoValue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn rescans_on_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in [
+            ("shard-2", "30"),
+            ("shard-0", "10"),
+            ("shard-1", "20\n"),
+            ("shard-x", "not indexed"),
+            ("other-3", "not indexed"),
+        ] {
+            std::fs::write(dir.path().join(name), contents).unwrap();
+        }
+        let clock = MockClock::new();
+        let mut source = IndexedSource::<u16>::new(dir.path().to_owned(), "shard");
+        source
+            .set_refresh_interval(Some(Duration::from_secs(10)))
+            .set_clock(Arc::new(clock.clone()));
+        assert_eq!(source.value().unwrap(), [10, 20, 30]);
+
+        std::fs::write(dir.path().join("shard-3"), "40").unwrap();
+        assert_eq!(source.value().unwrap(), [10, 20, 30]);
+        clock.advance(Duration::from_secs(11));
+        assert_eq!(source.value().unwrap(), [10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn unreadable_shard_fails_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("shard-0"), "10").unwrap();
+        let source = IndexedSource::<u16>::new(dir.path().to_owned(), "shard");
+        assert_eq!(source.value().unwrap(), [10]);
+
+        std::fs::create_dir(dir.path().join("shard-1")).unwrap();
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::IOError(_))
+        ));
+        assert_eq!(source.value().unwrap(), [10]);
+    }
+}
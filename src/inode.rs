@@ -0,0 +1,91 @@
+use std::{fs::Metadata, os::unix::fs::MetadataExt, str::FromStr, sync::atomic::Ordering};
+
+use crate::FileSource;
+
+/// Identity of the file a source last read, telling a file modified in place apart from one
+/// replaced by a new file, e.g. by the `..data` symlink swap of a Kubernetes volume update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InodeMark {
+    pub dev: u64,
+    pub ino: u64,
+}
+
+impl From<&Metadata> for InodeMark {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Inode of the file read by the last refresh, `None` if it never read a file.
+    pub fn inode_mark(&self) -> Option<InodeMark> {
+        *self
+            .inode
+            .read()
+            .expect("error reading inode in FileSource")
+    }
+
+    /// Whether the last refresh read a different file than the one `prior` was taken from.
+    pub fn was_replaced_since(&self, prior: InodeMark) -> bool {
+        self.inode_mark().is_some_and(|mark| mark != prior)
+    }
+
+    pub(crate) fn record_inode(&self, metadata: &Metadata) {
+        let mark = InodeMark::from(metadata);
+        let previous = self
+            .inode
+            .write()
+            .expect("error locking inode in FileSource")
+            .replace(mark);
+        self.replaced.store(
+            previous.is_some_and(|previous| previous != mark),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(crate) fn reset_inode(&self) {
+        *self
+            .inode
+            .write()
+            .expect("error locking inode in FileSource") = None;
+        self.replaced.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn replaced_on_last_read(&self) -> bool {
+        self.replaced.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{ChangeInfo, TestSource};
+
+    #[test]
+    fn modify_vs_replace() {
+        let replaced = Arc::new(Mutex::new(Vec::new()));
+        let mut source: TestSource<String, true> = TestSource::new("first");
+        let seen = replaced.clone();
+        source.set_on_change_with_info(Box::new(move |_, info: &ChangeInfo| {
+            seen.lock().unwrap().push(info.replaced)
+        }));
+        source.refresh_value().unwrap();
+        let mark = source.inode_mark().unwrap();
+
+        source.write("modified");
+        source.refresh_value().unwrap();
+        assert!(!source.was_replaced_since(mark));
+
+        source.replace("replaced");
+        source.refresh_value().unwrap();
+        assert!(source.was_replaced_since(mark));
+
+        assert_eq!(*replaced.lock().unwrap(), [false, true]);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures_util::StreamExt;
+use inotify::{Inotify, WatchDescriptor, WatchMask};
+
+use crate::AsyncRefreshable;
+
+const EVENT_BUFFER_SIZE: usize = 4096;
+
+/// Events produced when Kubernetes atomically swaps the `..data` symlink of a volume, or a
+/// file is newly created.
+const WATCH_MASK: WatchMask = WatchMask::MOVED_TO.union(WatchMask::CREATE);
+
+/// Reloads sources as soon as their directory changes, using inotify directly.
+///
+/// Every source in a directory is refreshed on any event in it, since a symlink swap only
+/// reports the `..data` entry rather than the individual files behind it. Sources sharing a
+/// directory share a single watch, as watch descriptors are a limited kernel resource.
+pub struct InotifyWatcher {
+    inotify: Inotify,
+    /// Watch of each canonicalized directory.
+    watches: HashMap<PathBuf, WatchDescriptor>,
+    sources: HashMap<WatchDescriptor, Vec<Arc<dyn AsyncRefreshable>>>,
+}
+
+impl InotifyWatcher {
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            inotify: Inotify::init()?,
+            watches: HashMap::new(),
+            sources: HashMap::new(),
+        })
+    }
+
+    /// Watches the directory containing `source`, reusing the watch of an earlier source in
+    /// the same directory. Fails if the directory doesn't exist. A source that is already
+    /// watched, or reads the same file as one that is, is skipped so it isn't refreshed twice
+    /// per event.
+    pub fn watch(&mut self, source: Arc<dyn AsyncRefreshable>) -> io::Result<&mut Self> {
+        let path = source.path();
+        let dir = parent_dir(&path).canonicalize()?;
+        let watch = match self.watches.get(&dir) {
+            Some(watch) => watch.clone(),
+            None => {
+                let watch = self.inotify.watches().add(&dir, WATCH_MASK)?;
+                self.watches.insert(dir, watch.clone());
+                watch
+            }
+        };
+        let sources = self.sources.entry(watch).or_default();
+        // Sources of one watch share the canonical directory, so equal file names are the
+        // same file.
+        let watched = sources.iter().any(|watched| {
+            Arc::ptr_eq(watched, &source) || watched.path().file_name() == path.file_name()
+        });
+        if !watched {
+            sources.push(source);
+        }
+        Ok(self)
+    }
+
+    /// Number of directories watched.
+    pub fn watch_count(&self) -> usize {
+        self.watches.len()
+    }
+
+    /// Number of sources refreshed on events.
+    pub fn source_count(&self) -> usize {
+        self.sources.values().map(Vec::len).sum()
+    }
+
+    /// Refreshes sources on events until the event stream fails. Errors from refreshing a
+    /// source don't stop the watcher.
+    pub async fn run(self) -> io::Result<()> {
+        let mut events = self.inotify.into_event_stream(vec![0; EVENT_BUFFER_SIZE])?;
+        while let Some(event) = events.next().await {
+            let event = event?;
+            for source in self.sources.get(&event.wd).into_iter().flatten() {
+                let _ = source.refresh_async().await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn(self) -> tokio::task::JoinHandle<io::Result<()>> {
+        tokio::spawn(self.run())
+    }
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+        _ => PathBuf::from("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::symlink, time::Duration};
+
+    use super::*;
+    use crate::{FileSource, ValueSource};
+
+    #[tokio::test]
+    async fn symlink_swap_reloads() {
+        // Same layout as a Kubernetes ConfigMap volume
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("..v1")).unwrap();
+        fs::write(dir.path().join("..v1/KEY"), "first").unwrap();
+        symlink("..v1", dir.path().join("..data")).unwrap();
+        symlink("..data/KEY", dir.path().join("KEY")).unwrap();
+
+        let source: Arc<FileSource<String, true>> =
+            Arc::new(FileSource::from_path(dir.path().join("KEY")));
+        assert_eq!(source.value().unwrap(), "first");
+        let mut watcher = InotifyWatcher::new().unwrap();
+        watcher.watch(source.clone()).unwrap();
+        let handle = watcher.spawn();
+
+        fs::create_dir(dir.path().join("..v2")).unwrap();
+        fs::write(dir.path().join("..v2/KEY"), "second").unwrap();
+        symlink("..v2", dir.path().join("..data_tmp")).unwrap();
+        fs::rename(dir.path().join("..data_tmp"), dir.path().join("..data")).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while source.value().unwrap() != "second" {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        handle.abort();
+    }
+
+    #[test]
+    fn sources_in_one_directory_share_a_watch() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = InotifyWatcher::new().unwrap();
+        for name in ["HOST", "PORT", "./USER"] {
+            fs::write(dir.path().join(name), "value").unwrap();
+            let source: FileSource<String, true> = FileSource::from_path(dir.path().join(name));
+            watcher.watch(Arc::new(source)).unwrap();
+        }
+
+        assert_eq!(watcher.watch_count(), 1);
+        assert_eq!(watcher.source_count(), 3);
+    }
+
+    #[test]
+    fn watched_sources_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("PORT"), "8080").unwrap();
+        let source: Arc<FileSource<u16, true>> =
+            Arc::new(FileSource::from_path(dir.path().join("PORT")));
+        let same_file: FileSource<String, true> = FileSource::from_path(dir.path().join("./PORT"));
+        let mut watcher = InotifyWatcher::new().unwrap();
+        watcher
+            .watch(source.clone())
+            .unwrap()
+            .watch(source)
+            .unwrap()
+            .watch(Arc::new(same_file))
+            .unwrap();
+
+        assert_eq!(watcher.source_count(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+use std::{borrow::Cow, str::FromStr, sync::Arc};
+
+use crate::{FileSource, RefreshFileSourceError};
+
+/// Looks up the value of an environment variable, `None` when it is unset.
+pub type EnvLookup = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Replace `${NAME}` and `${NAME:-default}` placeholders with environment variables before
+    /// trimming and parsing. As in the shell, the default is used when the variable is unset or
+    /// empty. Refreshes fail with [`RefreshFileSourceError::UndefinedVariable`] for unset
+    /// variables without a default.
+    pub fn set_env_interpolation(&mut self, env_interpolation: bool) -> &mut Self {
+        self.env_interpolation = env_interpolation;
+        self
+    }
+
+    /// Resolve placeholders with `lookup` instead of the process environment, `None` restores
+    /// the process environment.
+    pub fn set_env_lookup(&mut self, lookup: Option<EnvLookup>) -> &mut Self {
+        self.env_lookup = lookup;
+        self
+    }
+}
+
+/// Looks up `name` with `lookup`, or in the process environment without one.
+pub(crate) fn lookup_env(lookup: Option<&EnvLookup>, name: &str) -> Option<String> {
+    match lookup {
+        Some(lookup) => lookup(name),
+        None => std::env::var(name).ok(),
+    }
+}
+
+/// Substitutes environment variables for placeholders in `contents`. An unterminated `${` is
+/// kept as is.
+pub(crate) fn interpolate_env<'a, E: std::fmt::Debug>(
+    contents: &'a str,
+    lookup: Option<&EnvLookup>,
+) -> Result<Cow<'a, str>, RefreshFileSourceError<E>> {
+    if !contents.contains("${") {
+        return Ok(Cow::Borrowed(contents));
+    }
+
+    let mut interpolated = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        interpolated.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..start + len];
+        let (name, default) = match placeholder.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (placeholder, None),
+        };
+        match (lookup_env(lookup, name), default) {
+            (Some(value), Some(default)) if value.is_empty() => interpolated.push_str(default),
+            (Some(value), _) => interpolated.push_str(&value),
+            (None, Some(default)) => interpolated.push_str(default),
+            (None, None) => {
+                return Err(RefreshFileSourceError::UndefinedVariable(name.to_owned()));
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    interpolated.push_str(rest);
+
+    Ok(Cow::Owned(interpolated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValueError, ValueSource};
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> EnvLookup {
+        Arc::new(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn interpolates_port() {
+        let mut source: FileSource<u16, true> =
+            FileSource::from_path("sources/test-interpolate".into());
+        source
+            .set_env_interpolation(true)
+            .set_env_lookup(Some(env(&[("K8S_CONFIG_TEST_PORT", "8443")])));
+
+        assert_eq!(source.value().unwrap(), 8443);
+    }
+
+    #[test]
+    fn defaults_and_undefined() {
+        let lookup = env(&[("EMPTY", ""), ("HOST", "example.com")]);
+        assert_eq!(
+            interpolate_env::<()>(
+                "${UNSET:-localhost} ${EMPTY:-localhost} ${HOST:-localhost} ${EMPTY} ${",
+                Some(&lookup)
+            )
+            .unwrap(),
+            "localhost localhost example.com  ${"
+        );
+
+        let mut source: FileSource<String, true> =
+            FileSource::from_path("sources/test-interpolate-undefined".into());
+        source
+            .set_env_interpolation(true)
+            .set_env_lookup(Some(env(&[])));
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::UndefinedVariable(name)
+            )) if name == "K8S_CONFIG_TEST_UNDEFINED"
+        ));
+    }
+}
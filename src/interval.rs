@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// Refresh interval shared by a set of sources, so a service can standardize on one cadence
+/// without calling [`FileSource::set_refresh_interval`](crate::FileSource::set_refresh_interval)
+/// on every source. The policy is the last type parameter of [`FileSource`](crate::FileSource),
+/// so it shows up in the types that hold the sources:
+///
+/// ```
+/// use std::time::Duration;
+/// use k8s_config::{FileSource, IntervalPolicy};
+///
+/// struct Standard;
+///
+/// impl IntervalPolicy for Standard {
+///     const INTERVAL: Option<Duration> = Some(Duration::from_secs(30));
+/// }
+///
+/// type StandardSource<T> = FileSource<T, true, Standard>;
+///
+/// let source: StandardSource<String> = FileSource::from_path("sources/test-required".into());
+/// ```
+pub trait IntervalPolicy {
+    const INTERVAL: Option<Duration>;
+}
+
+/// Only reads the file once, the default policy of [`FileSource`](crate::FileSource).
+#[derive(Debug, Clone, Copy)]
+pub struct NoRefresh;
+
+impl IntervalPolicy for NoRefresh {
+    const INTERVAL: Option<Duration> = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{FileSource, TestSource, ValueSource};
+
+    struct TenSeconds;
+
+    impl IntervalPolicy for TenSeconds {
+        const INTERVAL: Option<Duration> = Some(Duration::from_secs(10));
+    }
+
+    #[test]
+    fn policy_interval_used() {
+        let test: TestSource<String, true> = TestSource::new("first");
+        let mut source: FileSource<String, true, TenSeconds> =
+            FileSource::from_path(test.path().to_owned());
+        source.set_clock(Arc::new(test.clock().clone()));
+        assert_eq!(source.value().unwrap(), "first");
+
+        test.write("second");
+        test.clock().advance(Duration::from_secs(9));
+        assert_eq!(source.value().unwrap(), "first");
+        test.clock().advance(Duration::from_secs(2));
+        assert_eq!(source.value().unwrap(), "second");
+    }
+}
@@ -0,0 +1,293 @@
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    FileSource, RefreshFileSourceError, ValueError, ValueOutcome, ValueSource, fingerprint,
+};
+
+struct Parsed<T> {
+    document: Value,
+    value: T,
+}
+
+struct State<T> {
+    /// Last document that deserialized, and passed the schema if there is one.
+    good: Option<Parsed<T>>,
+    /// Fingerprint of the contents last deserialized or rejected.
+    seen: Option<u64>,
+    last_error: Option<Arc<RefreshFileSourceError<serde_json::Error>>>,
+}
+
+/// Source deserializing a JSON document into `T`. The file is only deserialized again when
+/// its contents change.
+///
+/// A changed document that fails to deserialize or to validate keeps the previous value, with
+/// the error available from [`JsonSource::last_error`]. Without a previous value the error is
+/// returned instead.
+pub struct JsonSource<T, const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    merge: bool,
+    #[cfg(feature = "jsonschema")]
+    schema: Option<jsonschema::Validator>,
+    state: Mutex<State<T>>,
+}
+
+impl<T: DeserializeOwned + Clone, const REQUIRED: bool> JsonSource<T, REQUIRED> {
+    pub fn new(source: FileSource<String, REQUIRED>) -> Self {
+        Self {
+            source,
+            merge: false,
+            #[cfg(feature = "jsonschema")]
+            schema: None,
+            state: Mutex::new(State {
+                good: None,
+                seen: None,
+                last_error: None,
+            }),
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    /// Merge each new document into the previously parsed one before deserializing, so a file
+    /// only containing the changed fields keeps the earlier values of all others. Objects are
+    /// merged recursively, any other value replaces the previous one.
+    pub fn set_merge(&mut self, merge: bool) -> &mut Self {
+        self.merge = merge;
+        self
+    }
+
+    /// Validate each document against the JSON Schema `schema` before deserializing it,
+    /// failing with [`crate::SchemaError`] listing every violation. Fails right away if
+    /// `schema` is not a valid schema itself.
+    #[cfg(feature = "jsonschema")]
+    pub fn set_json_schema(
+        &mut self,
+        schema: Option<&Value>,
+    ) -> Result<&mut Self, crate::SchemaError> {
+        self.schema = schema.map(crate::schema::compile).transpose()?;
+        Ok(self)
+    }
+
+    /// Error of the current document if it was rejected in favour of the previous value.
+    /// Cleared once a document is accepted again.
+    pub fn last_error(&self) -> Option<Arc<RefreshFileSourceError<serde_json::Error>>> {
+        self.state
+            .lock()
+            .expect("error locking state in JsonSource")
+            .last_error
+            .clone()
+    }
+
+    fn parsed(&self) -> Result<Option<T>, ValueError<serde_json::Error>> {
+        let ValueOutcome::Loaded(contents) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+        let fingerprint = fingerprint(&contents);
+        let mut state = self
+            .state
+            .lock()
+            .expect("error locking state in JsonSource");
+        if let (true, Some(good)) = (state.seen == Some(fingerprint), &state.good) {
+            return Ok(Some(good.value.clone()));
+        }
+
+        match self.parse(&contents, state.good.as_ref()) {
+            Ok(parsed) => {
+                let value = parsed.value.clone();
+                *state = State {
+                    good: Some(parsed),
+                    seen: Some(fingerprint),
+                    last_error: None,
+                };
+                Ok(Some(value))
+            }
+            Err(e) => {
+                let Some(good) = &state.good else {
+                    return Err(e.into());
+                };
+                let value = good.value.clone();
+                state.seen = Some(fingerprint);
+                state.last_error = Some(Arc::new(e));
+                Ok(Some(value))
+            }
+        }
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        previous: Option<&Parsed<T>>,
+    ) -> Result<Parsed<T>, RefreshFileSourceError<serde_json::Error>> {
+        let mut document: Value =
+            serde_json::from_str(contents).map_err(RefreshFileSourceError::ParseError)?;
+        if let (true, Some(previous)) = (self.merge, previous) {
+            let mut merged = previous.document.clone();
+            merge(&mut merged, document);
+            document = merged;
+        }
+        #[cfg(feature = "jsonschema")]
+        if let Some(schema) = &self.schema {
+            crate::schema::check(schema, &document).map_err(RefreshFileSourceError::SchemaError)?;
+        }
+        let value = T::deserialize(&document).map_err(RefreshFileSourceError::ParseError)?;
+        Ok(Parsed { document, value })
+    }
+}
+
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+impl<T: DeserializeOwned + Clone> ValueSource<T, serde_json::Error> for JsonSource<T, true> {
+    fn value(&self) -> Result<T, ValueError<serde_json::Error>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<T: DeserializeOwned + Clone> ValueSource<Option<T>, serde_json::Error>
+    for JsonSource<T, false>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<serde_json::Error>> {
+        self.parsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::TestSource;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct Settings {
+        host: String,
+        port: u16,
+        limits: Limits,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct Limits {
+        connections: u32,
+        requests: u32,
+    }
+
+    #[test]
+    fn merge_keeps_unspecified_fields() {
+        let test: TestSource<String, true> = TestSource::new(
+            r#"{"host": "db", "port": 5432, "limits": {"connections": 10, "requests": 100}}"#,
+        );
+        let mut source =
+            JsonSource::<Settings, true>::new(FileSource::from_path(test.path().to_owned()));
+        source.set_merge(true);
+        assert_eq!(source.value().unwrap().port, 5432);
+
+        test.write(r#"{"port": 6432, "limits": {"requests": 200}}"#);
+        source.source_mut().refresh_value().unwrap();
+        assert_eq!(
+            source.value().unwrap(),
+            Settings {
+                host: "db".to_owned(),
+                port: 6432,
+                limits: Limits {
+                    connections: 10,
+                    requests: 200
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn without_merge_documents_are_complete() {
+        let test: TestSource<String, true> = TestSource::new(
+            r#"{"host": "db", "port": 5432, "limits": {"connections": 10, "requests": 100}}"#,
+        );
+        let mut source =
+            JsonSource::<Settings, true>::new(FileSource::from_path(test.path().to_owned()));
+        assert_eq!(source.value().unwrap().host, "db");
+
+        test.write(r#"{"port": 6432}"#);
+        source.source_mut().refresh_value().unwrap();
+        assert_eq!(source.value().unwrap().port, 5432);
+        assert!(matches!(
+            source.last_error().as_deref(),
+            Some(RefreshFileSourceError::ParseError(_))
+        ));
+
+        let missing =
+            JsonSource::<Settings, true>::new(FileSource::from_path(test.path().to_owned()));
+        assert!(matches!(
+            missing.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(_)
+            ))
+        ));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn schema_violations_are_reported() {
+        let test: TestSource<String, true> = TestSource::new(
+            r#"{"host": "db", "port": 5432, "limits": {"connections": 10, "requests": 100}}"#,
+        );
+        let mut source =
+            JsonSource::<Settings, true>::new(FileSource::from_path(test.path().to_owned()));
+        source
+            .set_json_schema(Some(&serde_json::json!({
+                "type": "object",
+                "required": ["host", "port"],
+                "properties": {
+                    "limits": {
+                        "type": "object",
+                        "properties": {"connections": {"maximum": 100}}
+                    }
+                }
+            })))
+            .unwrap();
+        assert_eq!(source.value().unwrap().port, 5432);
+
+        test.write(r#"{"host": "db", "limits": {"connections": 1000, "requests": 100}}"#);
+        source.source_mut().refresh_value().unwrap();
+        assert_eq!(source.value().unwrap().port, 5432);
+        let last_error = source.last_error();
+        let Some(RefreshFileSourceError::SchemaError(e)) = last_error.as_deref() else {
+            panic!("expected a schema violation");
+        };
+        assert_eq!(
+            e.0,
+            [
+                r#""port" is a required property"#,
+                "/limits/connections: 1000 is greater than the maximum of 100",
+            ]
+        );
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn invalid_schema_is_rejected() {
+        let mut source =
+            JsonSource::<Settings, true>::new(FileSource::from_path("sources/test-port".into()));
+        assert!(
+            source
+                .set_json_schema(Some(&serde_json::json!({"type": 5})))
+                .is_err()
+        );
+    }
+}
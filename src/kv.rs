@@ -0,0 +1,185 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome};
+
+const DEFAULT_SEPARATOR: char = '=';
+const DEFAULT_COMMENT_PREFIX: &str = "#";
+
+/// How to handle a key appearing more than once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    KeepFirst,
+    #[default]
+    KeepLast,
+    Error,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum KvParseError {
+    #[error("line {0} is missing the key/value separator")]
+    MissingSeparator(usize),
+    #[error("duplicate key: {0}")]
+    DuplicateKey(String),
+}
+
+/// Source parsing `key=value` lines into a map.
+///
+/// Empty lines and lines starting with the comment prefix are skipped, keys and values are
+/// trimmed. The map is parsed from the cached file contents on each access.
+pub struct KvFileSource<const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    separator: char,
+    comment_prefix: String,
+    duplicate_policy: DuplicateKeyPolicy,
+}
+
+impl<const REQUIRED: bool> KvFileSource<REQUIRED> {
+    pub fn new(source: FileSource<String, REQUIRED>) -> Self {
+        Self {
+            source,
+            separator: DEFAULT_SEPARATOR,
+            comment_prefix: DEFAULT_COMMENT_PREFIX.to_owned(),
+            duplicate_policy: DuplicateKeyPolicy::default(),
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    pub fn set_separator(&mut self, separator: char) -> &mut Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn set_comment_prefix(&mut self, comment_prefix: impl Into<String>) -> &mut Self {
+        self.comment_prefix = comment_prefix.into();
+        self
+    }
+
+    pub fn set_duplicate_policy(&mut self, duplicate_policy: DuplicateKeyPolicy) -> &mut Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// Returns all entries, an empty map if an optional source's file is absent.
+    pub fn as_map(&self) -> Result<HashMap<String, String>, ValueError<KvParseError>> {
+        match self.source.refreshed_raw()? {
+            ValueOutcome::Loaded(contents) => Ok(self.parse(&contents)?),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing if REQUIRED => {
+                Err(ValueError::NoValue)
+            }
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => Ok(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, ValueError<KvParseError>> {
+        Ok(self.as_map()?.remove(key))
+    }
+
+    /// Keys of all entries, sorted.
+    pub fn keys(&self) -> Result<Vec<String>, ValueError<KvParseError>> {
+        let mut keys: Vec<_> = self.as_map()?.into_keys().collect();
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+    ) -> Result<HashMap<String, String>, RefreshFileSourceError<KvParseError>> {
+        let mut map = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(&self.comment_prefix) {
+                continue;
+            }
+
+            let (key, value) =
+                line.split_once(self.separator)
+                    .ok_or(RefreshFileSourceError::ParseError(
+                        KvParseError::MissingSeparator(index + 1),
+                    ))?;
+            let (key, value) = (key.trim().to_owned(), value.trim().to_owned());
+            match (map.entry(key), self.duplicate_policy) {
+                (Entry::Vacant(entry), _) => {
+                    entry.insert(value);
+                }
+                (Entry::Occupied(_), DuplicateKeyPolicy::KeepFirst) => {}
+                (Entry::Occupied(mut entry), DuplicateKeyPolicy::KeepLast) => {
+                    entry.insert(value);
+                }
+                (Entry::Occupied(entry), DuplicateKeyPolicy::Error) => {
+                    return Err(RefreshFileSourceError::ParseError(
+                        KvParseError::DuplicateKey(entry.key().clone()),
+                    ));
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duplicate_source(policy: DuplicateKeyPolicy) -> KvFileSource<true> {
+        let mut source =
+            KvFileSource::new(FileSource::from_path("sources/test-kv-duplicate".into()));
+        source.set_duplicate_policy(policy);
+        source
+    }
+
+    #[test]
+    fn duplicate_keep_first() {
+        let source = duplicate_source(DuplicateKeyPolicy::KeepFirst);
+
+        assert_eq!(source.get("host").unwrap().as_deref(), Some("db.local"));
+        assert_eq!(source.get("port").unwrap().as_deref(), Some("5432"));
+    }
+
+    #[test]
+    fn duplicate_keep_last() {
+        let source = duplicate_source(DuplicateKeyPolicy::KeepLast);
+
+        assert_eq!(source.get("host").unwrap().as_deref(), Some("db2.local"));
+        assert_eq!(source.as_map().unwrap().len(), 2);
+        assert_eq!(source.keys().unwrap(), ["host", "port"]);
+    }
+
+    #[test]
+    fn duplicate_error() {
+        let source = duplicate_source(DuplicateKeyPolicy::Error);
+
+        assert!(matches!(
+            source.as_map(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(KvParseError::DuplicateKey(key))
+            )) if key == "host"
+        ));
+    }
+
+    #[test]
+    fn custom_separator_and_comment() {
+        let mut source: KvFileSource<true> =
+            KvFileSource::new(FileSource::from_path("sources/test-kv-duplicate".into()));
+        source.set_separator(':').set_comment_prefix("host");
+
+        assert!(matches!(
+            source.as_map(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(KvParseError::MissingSeparator(1))
+            ))
+        ));
+    }
+
+    #[test]
+    fn optional_missing_is_empty() {
+        let source: KvFileSource<false> =
+            KvFileSource::new(FileSource::from_path("sources/test-kv-missing".into()));
+
+        assert!(source.as_map().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use crate::{FileSource, ValueError, ValueSource};
+
+/// Read-once value of a required source: the file is read on first access and the value kept
+/// forever after, without any refresh bookkeeping. A failed read is retried on the next access.
+pub struct LazyValue<T: FromStr + Clone>
+where
+    T::Err: std::fmt::Debug,
+{
+    source: FileSource<T, true>,
+    value: OnceLock<T>,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> LazyValue<T> {
+    pub fn new(source: FileSource<T, true>) -> Self {
+        Self {
+            source,
+            value: OnceLock::new(),
+        }
+    }
+
+    pub fn get(&self) -> Result<&T, ValueError<E>> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let value = self.source.value()?;
+        // a concurrent first access may have won, both read the same file
+        Ok(self.value.get_or_init(|| value))
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> FileSource<T, true> {
+    pub fn into_lazy(self) -> LazyValue<T> {
+        LazyValue::new(self)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<T, E> for LazyValue<T> {
+    fn value(&self) -> Result<T, ValueError<E>> {
+        self.get().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io,
+        path::Path,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{Opener, TestSource};
+
+    #[derive(Default)]
+    struct CountingOpener(AtomicUsize);
+
+    impl Opener for CountingOpener {
+        fn open(&self, path: &Path) -> io::Result<File> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            File::open(path)
+        }
+    }
+
+    #[test]
+    fn reads_once() {
+        let opener = Arc::new(CountingOpener::default());
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source
+            .set_opener(opener.clone())
+            .set_refresh_interval(Some(Duration::ZERO));
+        let lazy = source.into_lazy();
+
+        for _ in 0..10 {
+            assert_eq!(*lazy.get().unwrap(), 8080);
+            assert_eq!(lazy.value().unwrap(), 8080);
+        }
+        assert_eq!(opener.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn retries_failed_read() {
+        let test: TestSource<u16, true> = TestSource::missing();
+        let lazy = FileSource::<u16, true>::from_path(test.path().to_owned()).into_lazy();
+        assert!(lazy.get().is_err());
+
+        test.write("8080");
+        assert_eq!(*lazy.get().unwrap(), 8080);
+    }
+}
@@ -1,27 +1,316 @@
+// Lets code generated by the derive macros refer to `::k8s_config` inside this crate as well.
+extern crate self as k8s_config;
+
 use std::{
-    fs::File,
+    borrow::Cow,
+    collections::VecDeque,
+    convert::Infallible,
     io::Read,
+    marker::PhantomData,
     path::PathBuf,
     str::FromStr,
-    sync::RwLock,
-    time::{Duration, Instant},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
+mod adapters;
+mod allowed_root;
+#[cfg(feature = "tokio")]
+mod async_refresh;
+mod backend;
+mod barrier;
+mod cgroup;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod circuit_breaker;
+mod clock;
+mod concat;
+mod conditional;
+#[cfg(feature = "config")]
+mod config_source;
+mod contextual;
+mod dir;
+#[cfg(feature = "docker-config")]
+mod docker_config;
+mod duration;
+#[cfg(feature = "encoding_rs")]
+mod encoding;
+mod error_map;
+mod fixed_key;
+mod group;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod history;
+mod host_port;
+mod indexed;
+#[cfg(unix)]
+mod inode;
+#[cfg(all(feature = "inotify", target_os = "linux"))]
+mod inotify_watch;
+mod interpolate;
+mod interval;
+#[cfg(feature = "json")]
+mod json;
+mod kv;
+mod lazy;
+mod list;
+mod location;
+mod macros;
+#[cfg(feature = "manifest")]
+mod manifest;
+#[cfg(feature = "memmap2")]
+mod mmap;
+mod multi_parse;
+mod observer;
+mod opener;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod path_source;
+#[cfg(unix)]
+mod permissions;
+mod refresh_window;
+mod registry;
+mod removed;
+mod scalar;
+#[cfg(feature = "jsonschema")]
+mod schema;
+mod shared_read;
+#[cfg(all(feature = "signal", unix))]
+mod signal;
+mod skip_unchanged;
+mod stamp;
+mod stats;
+#[cfg(feature = "futures")]
+mod subscribe;
+#[cfg(any(test, feature = "test-util"))]
+mod test_util;
+#[cfg(unix)]
+mod unix_socket;
+mod validator;
+mod version_gate;
+
+pub use adapters::{OptionalSource, RequiredSource};
+#[cfg(feature = "tokio")]
+pub use async_refresh::{AsyncRefreshable, RefreshFuture};
+use backend::ReadRecord;
+pub use backend::{Fetched, FsBackend, SecretBackend};
+pub use barrier::{ReloadBarrier, ReloadCallback};
+pub use cgroup::{CGROUP_MEMORY_MAX, CgroupLimit, CgroupLimitSource};
+pub use circuit_breaker::CircuitBreakerSource;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use concat::{ConcatOrder, ConcatSource};
+pub use conditional::ConditionallyRequiredSource;
+pub use contextual::{ContextFileSource, ContextualParser};
+pub use dir::{DirFiles, DirSource, FromDir, FromDirError};
+#[cfg(feature = "docker-config")]
+pub use docker_config::{DockerConfigError, DockerConfigSource};
+pub use duration::{DurationParseError, DurationValue};
+pub use error_map::{ErrorMap, ErrorMappedSource, ParseContext};
+pub use fixed_key::{FixedKeyError, FixedKeySource};
+pub use group::{ConsistentView, PanicCallback, RefreshGroup, RefreshGroupError, Refresher};
+pub use host_port::{HostPort, HostPortParseError, HostPortSource};
+pub use indexed::IndexedSource;
+#[cfg(unix)]
+pub use inode::InodeMark;
+#[cfg(all(feature = "inotify", target_os = "linux"))]
+pub use inotify_watch::InotifyWatcher;
+pub use interpolate::EnvLookup;
+pub use interval::{IntervalPolicy, NoRefresh};
+#[cfg(feature = "json")]
+pub use json::JsonSource;
+/// Derives [`FromDir`] for a struct, mapping each field to the key of its name or
+/// `#[from_dir(key = "...")]`; `Option` fields are optional.
+#[cfg(feature = "derive")]
+pub use k8s_config_derive::FromDir;
+pub use kv::{DuplicateKeyPolicy, KvFileSource, KvParseError};
+pub use lazy::LazyValue;
+pub use list::ListFileSource;
+pub use location::Location;
+#[cfg(feature = "manifest")]
+pub use manifest::ManifestError;
+pub use multi_parse::{MultiParse, MultiParseError};
+pub use observer::{RefreshEvent, RefreshObserver};
+pub use opener::{FsOpener, Opener};
+#[cfg(feature = "opentelemetry")]
+pub use otel::OtelObserver;
+pub use path_source::PathFileSource;
+#[cfg(unix)]
+pub use permissions::PermissionCallback;
+pub use refresh_window::RefreshWindow;
+pub use registry::{ConfigValue, ErasedSource, RegistryError, SourceRegistry};
+pub use removed::RemovedCallback;
+pub use scalar::{read_bool, read_i64, read_string, read_u16, read_u32, read_u64};
+#[cfg(feature = "jsonschema")]
+pub use schema::SchemaError;
+pub use shared_read::SharedReadCache;
+#[cfg(all(feature = "signal", unix))]
+pub use signal::{Signal, SignalReloader, reload_on_signal};
+pub use stats::SourceStats;
+#[cfg(any(test, feature = "test-util"))]
+pub use test_util::TestSource;
+#[cfg(unix)]
+pub use unix_socket::UnixSocketSource;
+pub use validator::{ValidationError, Validator};
+pub use version_gate::{VersionGateError, VersionGatedSource, VersionParser};
+
 const INITIAL_READ_BUFFER_CAPACITY: usize = 128;
+const DEFAULT_DEADLINE_THRESHOLD: Duration = Duration::from_millis(5);
 
-pub struct FileSource<T: FromStr + Clone, const REQUIRED: bool> {
-    filepath: PathBuf,
-    value: RwLock<Option<T>>,
+/// `I` is the [`IntervalPolicy`] giving the refresh interval a new source starts with.
+pub struct FileSource<T: FromStr + Clone, const REQUIRED: bool, I = NoRefresh>
+where
+    T::Err: std::fmt::Debug,
+{
+    location: RwLock<Location>,
+    value: RwLock<ValueOutcome<T>>,
     refresh_interval: Option<Duration>,
     last_refresh: RwLock<Option<Instant>>,
+    min_retry_interval: Option<Duration>,
+    last_failed_refresh: RwLock<Option<Instant>>,
     auto_trim: bool,
+    on_empty: Option<OnEmpty>,
+    normalize_newlines: bool,
+    strip_bom: bool,
+    max_lines: Option<usize>,
+    #[cfg(feature = "checksum")]
+    expected_sha256: Option<String>,
+    inline_comment: Option<char>,
+    env_interpolation: bool,
+    env_lookup: Option<EnvLookup>,
+    #[cfg(feature = "encoding_rs")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+    keep_last_good: bool,
+    errors_are_none: bool,
+    last_error: RwLock<Option<Arc<RefreshFileSourceError<T::Err>>>>,
+    prefetch_ratio: Option<f64>,
+    deadline_threshold: Duration,
+    freeze: RwLock<Freeze<T>>,
+    validator: Option<Validator<T>>,
+    on_change: Option<ChangeInfoCallback<T>>,
+    on_removed: Option<RemovedCallback>,
+    refresh_window: Option<RefreshWindow>,
+    present: AtomicBool,
+    change_comparator: Option<ChangeComparator<T>>,
+    clock: Arc<dyn Clock>,
+    backend: Arc<dyn SecretBackend>,
+    observer: Option<Arc<dyn RefreshObserver>>,
+    refresh_counters: stats::RefreshCounters,
+    read_timeout: Option<Duration>,
+    /// Set while a helper thread of a timed read is running.
+    read_in_flight: Arc<AtomicBool>,
+    shared_read_cache: Option<Arc<SharedReadCache>>,
+    ready_marker: Option<PathBuf>,
+    allowed_root: Option<PathBuf>,
+    /// Reused across refreshes to avoid reallocating for every read.
+    read_buf: Mutex<Vec<u8>>,
+    last_read_bytes: RwLock<Option<usize>>,
+    read_modified: RwLock<Option<SystemTime>>,
+    skip_unchanged: bool,
+    last_read_mark: RwLock<Option<skip_unchanged::ReadMark>>,
+    modified: RwLock<Option<SystemTime>>,
+    generation: AtomicU64,
+    /// Hash of the contents the cached value was parsed from, telling changed values apart.
+    fingerprint: RwLock<Option<u64>>,
+    /// Set by [`FileSource::evict`], letting the next refresh bypass the freeze, ready marker
+    /// and refresh window to restore the value.
+    evicted: AtomicBool,
+    history_capacity: usize,
+    history: Mutex<VecDeque<(Instant, T)>>,
+    #[cfg(feature = "futures")]
+    subscribers: Mutex<subscribe::Subscribers<T>>,
+    #[cfg(unix)]
+    inode: RwLock<Option<InodeMark>>,
+    #[cfg(unix)]
+    replaced: AtomicBool,
+    #[cfg(unix)]
+    check_permissions: bool,
+    #[cfg(unix)]
+    on_insecure_permissions: Option<PermissionCallback>,
+    #[cfg(feature = "gzip")]
+    sniff_gzip: bool,
+    #[cfg(feature = "memmap2")]
+    mmap: bool,
+    #[cfg(feature = "memmap2")]
+    mapped: RwLock<Option<mmap::MappedFile>>,
+    policy: PhantomData<fn() -> I>,
+}
+
+pub type ChangeCallback<T> = Box<dyn Fn(&T) + Send + Sync>;
+pub type ChangeInfoCallback<T> = Box<dyn Fn(&T, &ChangeInfo) + Send + Sync>;
+
+/// Details passed to change callbacks along with the new value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChangeInfo {
+    /// The file was replaced by a new one (a new inode) rather than modified in place. Always
+    /// `false` on non-Unix platforms.
+    pub replaced: bool,
+}
+/// Returns `true` if the two values (old, new) should be considered different.
+pub type ChangeComparator<T> = Box<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+enum Freeze<T> {
+    Thawed,
+    Frozen,
+    /// Keeps refreshing, but errors if the value would change.
+    Strict(fn(&T, &T) -> bool),
+}
+
+/// Policy for files that are empty, e.g. after a ConfigMap key was cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnEmpty {
+    /// Fail the refresh with [`RefreshFileSourceError::EmptyValue`].
+    Error,
+    /// Handle the source as if the file was missing.
+    TreatAsMissing,
+    /// Keep the last loaded value; without one, the file is treated as missing.
+    KeepPrevious,
+}
+
+/// Result of the last refresh, keeping track of why no value is present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueOutcome<T> {
+    Loaded(T),
+    FileAbsent,
+    EmptyTreatedMissing,
+}
+
+impl<T> ValueOutcome<T> {
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            ValueOutcome::Loaded(value) => Some(value),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => None,
+        }
+    }
 }
 
 pub trait ValueSource<T, E: std::fmt::Debug> {
     fn value(&self) -> Result<T, ValueError<E>>;
 }
 
+/// Lazily refreshes and yields the value of each source.
+pub fn values<'a, T: 'a, E: std::fmt::Debug + 'a>(
+    sources: impl IntoIterator<Item = &'a dyn ValueSource<T, E>>,
+) -> impl Iterator<Item = Result<T, ValueError<E>>> {
+    sources.into_iter().map(|source| source.value())
+}
+
+/// Type-erased handle to a source that can be refreshed, independent of its value type.
+pub trait Refreshable: Send + Sync {
+    fn path(&self) -> PathBuf;
+    fn refresh(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Counter bumped whenever a refresh stores a changed value; sources without one report 0.
+    fn generation(&self) -> u64 {
+        0
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum RefreshFileSourceError<E: std::fmt::Debug> {
     #[error("error reading config from file: {0}")]
     IOError(#[from] std::io::Error),
@@ -29,52 +318,581 @@ pub enum RefreshFileSourceError<E: std::fmt::Debug> {
     ParseError(E),
     #[error("no value given/file found")]
     NoValue,
+    #[error("file is empty")]
+    EmptyValue,
+    #[cfg(unix)]
+    #[error("error connecting to socket: {0}")]
+    ConnectError(std::io::Error),
+    #[error("reading the file timed out")]
+    Timeout,
+    #[error("file has more than {0} lines")]
+    TooManyLines(usize),
+    #[cfg(feature = "checksum")]
+    #[error("SHA-256 checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[cfg(feature = "encoding_rs")]
+    #[error("file is not valid {0}")]
+    DecodeError(&'static str),
+    #[cfg(feature = "jsonschema")]
+    #[error("document violates schema: {0}")]
+    SchemaError(SchemaError),
+    #[error("environment variable {0} is not set")]
+    UndefinedVariable(String),
+    #[error("{0:?} resolves outside the allowed root")]
+    PathEscape(PathBuf),
+    #[error("value changed after the source was frozen")]
+    FrozenValueChanged,
+    #[error("value failed validation: {0}")]
+    ValidationError(#[from] ValidationError),
+}
+
+impl RefreshFileSourceError<Infallible> {
+    /// Converts the error of a source that cannot fail parsing into any other parse error type.
+    pub fn widen<E: std::fmt::Debug>(self) -> RefreshFileSourceError<E> {
+        match self {
+            RefreshFileSourceError::IOError(e) => RefreshFileSourceError::IOError(e),
+            RefreshFileSourceError::ParseError(never) => match never {},
+            RefreshFileSourceError::NoValue => RefreshFileSourceError::NoValue,
+            RefreshFileSourceError::EmptyValue => RefreshFileSourceError::EmptyValue,
+            #[cfg(unix)]
+            RefreshFileSourceError::ConnectError(e) => RefreshFileSourceError::ConnectError(e),
+            RefreshFileSourceError::Timeout => RefreshFileSourceError::Timeout,
+            RefreshFileSourceError::TooManyLines(max_lines) => {
+                RefreshFileSourceError::TooManyLines(max_lines)
+            }
+            #[cfg(feature = "checksum")]
+            RefreshFileSourceError::ChecksumMismatch { expected, actual } => {
+                RefreshFileSourceError::ChecksumMismatch { expected, actual }
+            }
+            #[cfg(feature = "encoding_rs")]
+            RefreshFileSourceError::DecodeError(encoding) => {
+                RefreshFileSourceError::DecodeError(encoding)
+            }
+            #[cfg(feature = "jsonschema")]
+            RefreshFileSourceError::SchemaError(e) => RefreshFileSourceError::SchemaError(e),
+            RefreshFileSourceError::UndefinedVariable(name) => {
+                RefreshFileSourceError::UndefinedVariable(name)
+            }
+            RefreshFileSourceError::PathEscape(path) => RefreshFileSourceError::PathEscape(path),
+            RefreshFileSourceError::FrozenValueChanged => {
+                RefreshFileSourceError::FrozenValueChanged
+            }
+            RefreshFileSourceError::ValidationError(e) => {
+                RefreshFileSourceError::ValidationError(e)
+            }
+        }
+    }
 }
 
-impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool>
-    FileSource<T, REQUIRED>
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I: IntervalPolicy>
+    FileSource<T, REQUIRED, I>
 {
     pub fn from_path(filepath: PathBuf) -> Self {
+        Self::from_location(Location::Path(filepath))
+    }
+
+    pub fn from_location(location: Location) -> Self {
         Self {
-            filepath,
+            location: RwLock::new(location),
             auto_trim: true,
-            value: RwLock::new(None),
-            refresh_interval: None,
+            on_empty: None,
+            normalize_newlines: false,
+            strip_bom: true,
+            max_lines: None,
+            #[cfg(feature = "checksum")]
+            expected_sha256: None,
+            inline_comment: None,
+            env_interpolation: false,
+            env_lookup: None,
+            #[cfg(feature = "encoding_rs")]
+            encoding: None,
+            keep_last_good: false,
+            errors_are_none: false,
+            last_error: RwLock::new(None),
+            value: RwLock::new(ValueOutcome::FileAbsent),
+            refresh_interval: I::INTERVAL,
             last_refresh: RwLock::new(None),
+            min_retry_interval: None,
+            last_failed_refresh: RwLock::new(None),
+            prefetch_ratio: None,
+            deadline_threshold: DEFAULT_DEADLINE_THRESHOLD,
+            freeze: RwLock::new(Freeze::Thawed),
+            validator: None,
+            on_change: None,
+            on_removed: None,
+            refresh_window: None,
+            present: AtomicBool::new(false),
+            change_comparator: None,
+            clock: Arc::new(SystemClock),
+            backend: backend::default_backend(),
+            observer: None,
+            refresh_counters: stats::RefreshCounters::default(),
+            read_timeout: None,
+            read_in_flight: Arc::new(AtomicBool::new(false)),
+            shared_read_cache: None,
+            ready_marker: None,
+            allowed_root: None,
+            read_buf: Mutex::new(Vec::with_capacity(INITIAL_READ_BUFFER_CAPACITY)),
+            last_read_bytes: RwLock::new(None),
+            read_modified: RwLock::new(None),
+            skip_unchanged: false,
+            last_read_mark: RwLock::new(None),
+            modified: RwLock::new(None),
+            generation: AtomicU64::new(0),
+            fingerprint: RwLock::new(None),
+            evicted: AtomicBool::new(false),
+            history_capacity: 0,
+            history: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "futures")]
+            subscribers: Mutex::new(subscribe::Subscribers::default()),
+            #[cfg(unix)]
+            inode: RwLock::new(None),
+            #[cfg(unix)]
+            replaced: AtomicBool::new(false),
+            #[cfg(unix)]
+            check_permissions: false,
+            #[cfg(unix)]
+            on_insecure_permissions: None,
+            #[cfg(feature = "gzip")]
+            sniff_gzip: false,
+            #[cfg(feature = "memmap2")]
+            mmap: false,
+            #[cfg(feature = "memmap2")]
+            mapped: RwLock::new(None),
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    fn location(&self) -> Location {
+        self.location
+            .read()
+            .expect("error reading location in FileSource")
+            .clone()
+    }
+
+    /// Drops the cached value to free its memory and returns whether it did; the next access
+    /// reads the file again, regardless of the refresh interval. Sources that couldn't read it
+    /// right away, because they are frozen, waiting for their ready marker or outside their
+    /// refresh window, keep their value.
+    pub fn evict(&self) -> bool {
+        let mut cached = self
+            .value
+            .write()
+            .expect("error locking value for FileSource");
+        let thawed = matches!(
+            *self
+                .freeze
+                .read()
+                .expect("error reading freeze state in FileSource"),
+            Freeze::Thawed
+        );
+        if !thawed || self.awaiting_ready_marker() || self.outside_refresh_window() {
+            return false;
+        }
+        *cached = ValueOutcome::FileAbsent;
+        self.evicted.store(true, Ordering::Release);
+        *self
+            .last_refresh
+            .write()
+            .expect("error getting mutable ref to last_refresh in FileSource") = None;
+        *self
+            .fingerprint
+            .write()
+            .expect("error locking fingerprint in FileSource") = None;
+        self.generation.fetch_add(1, Ordering::Release);
+        true
+    }
+
+    /// Switches the source to another file, e.g. after a volume layout change. The cached value
+    /// is kept until the next access, which reads the new file regardless of the refresh
+    /// interval.
+    pub fn set_path(&self, path: PathBuf) {
+        *self
+            .location
+            .write()
+            .expect("error locking location in FileSource") = Location::Path(path);
+        *self
+            .last_refresh
+            .write()
+            .expect("error locking last_refresh in FileSource") = None;
+        #[cfg(unix)]
+        self.reset_inode();
+        #[cfg(feature = "memmap2")]
+        {
+            *self
+                .mapped
+                .write()
+                .expect("error locking mapped file in FileSource") = None;
         }
     }
 
+    /// Skip refreshes, keeping the previous value, until `marker` exists, for writers that drop
+    /// a sentinel file once all files are consistently written.
+    pub fn set_ready_marker(&mut self, marker: Option<PathBuf>) -> &mut Self {
+        self.ready_marker = marker;
+        self
+    }
+
+    fn awaiting_ready_marker(&self) -> bool {
+        self.ready_marker
+            .as_ref()
+            .is_some_and(|marker| !marker.exists())
+    }
+
     pub fn set_refresh_interval(&mut self, interval: Option<Duration>) -> &mut Self {
         self.refresh_interval = interval;
         self
     }
 
+    /// Minimum time between refresh attempts after a failed one, so a missing or broken file
+    /// isn't retried on every access. Throttled accesses fall back to the cached value.
+    /// Explicit [`FileSource::refresh_value`] calls are never throttled.
+    pub fn set_min_retry_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.min_retry_interval = interval;
+        self
+    }
+
     pub fn set_auto_trim(&mut self, auto_trim: bool) -> &mut Self {
         self.auto_trim = auto_trim;
         self
     }
 
-    fn set_value(&self, value: Option<T>) -> () {
+    /// Treat a file that is empty (after trimming, if enabled) as if it was missing.
+    ///
+    /// Shorthand for [`Self::set_on_empty`] with [`OnEmpty::TreatAsMissing`].
+    pub fn set_empty_as_missing(&mut self, empty_as_missing: bool) -> &mut Self {
+        self.set_on_empty(empty_as_missing.then_some(OnEmpty::TreatAsMissing))
+    }
+
+    /// How to handle a file that is empty (after trimming, if enabled). With `None`, the empty
+    /// string is parsed like any other content.
+    pub fn set_on_empty(&mut self, on_empty: Option<OnEmpty>) -> &mut Self {
+        self.on_empty = on_empty;
+        self
+    }
+
+    /// Fraction of the refresh interval after which [`Self::maybe_prefetch`] refreshes.
+    ///
+    /// Panics if `ratio` is not within `0.0..=1.0`.
+    pub fn set_prefetch_ratio(&mut self, ratio: f64) -> &mut Self {
+        assert!(
+            (0.0..=1.0).contains(&ratio),
+            "prefetch ratio must be within 0.0..=1.0"
+        );
+        self.prefetch_ratio = Some(ratio);
+        self
+    }
+
+    /// Minimum time that must be left until a deadline for
+    /// [`FileSource::value_with_deadline`] to perform a refresh.
+    pub fn set_deadline_threshold(&mut self, threshold: Duration) -> &mut Self {
+        self.deadline_threshold = threshold;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Fail refreshes of files with more than `max_lines` lines with
+    /// [`RefreshFileSourceError::TooManyLines`] before parsing, as a guard against runaway files.
+    pub fn set_max_lines(&mut self, max_lines: Option<usize>) -> &mut Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Convert `\r\n` and lone `\r` line endings to `\n` before parsing.
+    pub fn set_normalize_newlines(&mut self, normalize_newlines: bool) -> &mut Self {
+        self.normalize_newlines = normalize_newlines;
+        self
+    }
+
+    /// Strip a leading UTF-8 byte order mark, which trimming keeps. Enabled by default.
+    pub fn set_strip_bom(&mut self, strip_bom: bool) -> &mut Self {
+        self.strip_bom = strip_bom;
+        self
+    }
+
+    /// Strip everything from the first `comment` character to the end of each line before
+    /// parsing, e.g. `8080 # app port`. Off by default, as values may legitimately contain it.
+    pub fn set_strip_inline_comment(&mut self, comment: Option<char>) -> &mut Self {
+        self.inline_comment = comment;
+        self
+    }
+
+    /// Keep serving the last successfully parsed value if the file becomes unparseable; the
+    /// parse error is available from [`FileSource::last_error`] instead. Such refreshes still
+    /// count as errors in [`FileSource::stats`] and are reported to the observer as failed.
+    pub fn set_keep_last_good(&mut self, keep_last_good: bool) -> &mut Self {
+        self.keep_last_good = keep_last_good;
+        self
+    }
+
+    /// Rejects parsed values failing any of the validator's rules.
+    pub fn set_validator(&mut self, validator: Validator<T>) -> &mut Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Decides whether a refreshed value counts as changed for the on_change callback,
+    /// replacing the default `PartialEq` comparison.
+    pub fn set_change_comparator(&mut self, comparator: ChangeComparator<T>) -> &mut Self {
+        self.change_comparator = Some(comparator);
+        self
+    }
+
+    /// Pins the currently cached value, turning all future refreshes into no-ops.
+    pub fn freeze(&self) {
         *self
+            .freeze
+            .write()
+            .expect("error locking freeze state in FileSource") = Freeze::Frozen;
+    }
+
+    /// Stores `value`, parsed from contents hashing to `fingerprint`; `None` if it wasn't
+    /// parsed, e.g. for an unchanged file served from the cache.
+    fn set_value(
+        &self,
+        value: ValueOutcome<T>,
+        fingerprint: Option<u64>,
+    ) -> Result<(), RefreshFileSourceError<E>> {
+        let mut cached = self
             .value
             .write()
-            .expect("error locking value for FileSource") = value;
+            .expect("error locking value for FileSource");
+        let mut changed_value = None;
+        #[cfg(feature = "futures")]
+        let mut subscriber_update = None;
+        let freeze = self
+            .freeze
+            .read()
+            .expect("error reading freeze state in FileSource");
+        // The value was evicted before the source was frozen, restore it.
+        let freeze = if self.evicted.swap(false, Ordering::AcqRel) {
+            &Freeze::Thawed
+        } else {
+            &*freeze
+        };
+        match *freeze {
+            Freeze::Thawed => {
+                if let (true, ValueOutcome::Loaded(new)) = (self.history_capacity > 0, &value) {
+                    let changed = match &*cached {
+                        ValueOutcome::Loaded(old) => self
+                            .change_comparator
+                            .as_ref()
+                            .is_none_or(|changed| changed(old, new)),
+                        _ => true,
+                    };
+                    if changed {
+                        self.record_history(new);
+                    }
+                }
+                if let Some(on_change) = &self.on_change {
+                    let changed = match (&*cached, &value) {
+                        (ValueOutcome::Loaded(old), ValueOutcome::Loaded(new)) => self
+                            .change_comparator
+                            .as_ref()
+                            .is_none_or(|changed| changed(old, new)),
+                        (_, ValueOutcome::Loaded(_)) => self.last_refresh().is_some(),
+                        _ => false,
+                    };
+                    if let (true, ValueOutcome::Loaded(new)) = (changed, &value) {
+                        changed_value = Some((on_change, new.clone()));
+                    }
+                }
+                #[cfg(feature = "futures")]
+                {
+                    subscriber_update = self.subscriber_update(&cached, &value);
+                }
+                self.store_modified(&value);
+                let mut stored = self
+                    .fingerprint
+                    .write()
+                    .expect("error locking fingerprint in FileSource");
+                let changed = match (&*cached, &value) {
+                    (ValueOutcome::Loaded(_), ValueOutcome::Loaded(_)) => {
+                        fingerprint.is_some_and(|fingerprint| *stored != Some(fingerprint))
+                    }
+                    (cached, value) => {
+                        std::mem::discriminant(cached) != std::mem::discriminant(value)
+                    }
+                };
+                match (&value, fingerprint) {
+                    (ValueOutcome::Loaded(_), None) => {}
+                    (ValueOutcome::Loaded(_), fingerprint) => *stored = fingerprint,
+                    _ => *stored = None,
+                }
+                *cached = value;
+                // Bumped under the value lock, so a reader that sees the new value also sees this.
+                if changed {
+                    self.generation.fetch_add(1, Ordering::Release);
+                }
+            }
+            Freeze::Frozen => return Ok(()),
+            Freeze::Strict(eq) => {
+                let unchanged = match (&*cached, &value) {
+                    (ValueOutcome::Loaded(cached), ValueOutcome::Loaded(value)) => {
+                        eq(cached, value)
+                    }
+                    (cached, value) => {
+                        std::mem::discriminant(cached) == std::mem::discriminant(value)
+                    }
+                };
+                if !unchanged {
+                    return Err(RefreshFileSourceError::FrozenValueChanged);
+                }
+            }
+        }
+        drop(cached);
+        self.touch_last_refresh();
+        if let Some((on_change, value)) = changed_value {
+            let info = ChangeInfo {
+                #[cfg(unix)]
+                replaced: self.replaced_on_last_read(),
+                #[cfg(not(unix))]
+                replaced: false,
+            };
+            on_change(&value, &info);
+        }
+        #[cfg(feature = "futures")]
+        if let Some(value) = subscriber_update {
+            self.notify_subscribers(value);
+        }
+        Ok(())
+    }
+
+    /// Error of the last refresh that failed without being returned, e.g. a parse error hidden
+    /// by [`FileSource::set_keep_last_good`]. Cleared by the next successful refresh.
+    pub fn last_error(&self) -> Option<Arc<RefreshFileSourceError<E>>> {
+        self.last_error
+            .read()
+            .expect("error reading last_error in FileSource")
+            .clone()
+    }
+
+    fn set_last_error(&self, error: Option<Arc<RefreshFileSourceError<E>>>) {
+        *self
+            .last_error
+            .write()
+            .expect("error locking last_error in FileSource") = error;
+    }
+
+    /// Number of changed values stored by refreshes so far, see [`ConsistentView`]. Values are
+    /// compared by the contents they were parsed from, so refreshes of an unchanged file keep
+    /// the generation.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Number of bytes read from the file by the last refresh that read it.
+    pub fn last_read_bytes(&self) -> Option<usize> {
+        *self
+            .last_read_bytes
+            .read()
+            .expect("error reading last_read_bytes in FileSource")
+    }
+
+    fn set_last_read_bytes(&self, read_bytes: usize) {
+        *self
+            .last_read_bytes
+            .write()
+            .expect("error locking last_read_bytes in FileSource") = Some(read_bytes);
+    }
+
+    fn touch_last_refresh(&self) {
         *self
             .last_refresh
             .write()
             .expect("error getting mutable ref to last_refresh in FileSource") =
-            Some(Instant::now());
+            Some(self.clock.now());
     }
 
-    pub fn refresh_on_timeout(&self) -> Result<(), RefreshFileSourceError<E>> {
-        let last_refresh = self
-            .last_refresh
+    fn cached(&self) -> ValueOutcome<T> {
+        self.value
+            .read()
+            .expect("error reading value in FileSource")
+            .to_owned()
+    }
+
+    fn last_refresh(&self) -> Option<Instant> {
+        self.last_refresh
             .read()
             .expect("error reading last_refresh in FileSource")
-            .to_owned();
-        if last_refresh.is_none_or(|last_refresh| {
-            self.refresh_interval
-                .is_some_and(|refresh_interval| (last_refresh + refresh_interval) < Instant::now())
+            .to_owned()
+    }
+
+    fn needs_refresh(&self) -> bool {
+        refresh_due(self.last_refresh(), self.refresh_interval, self.clock.now())
+    }
+
+    pub fn refresh_on_timeout(&self) -> Result<(), RefreshFileSourceError<E>> {
+        self.refresh_if_due().map(|_| ())
+    }
+
+    /// Refreshes on timeout, returning whether the file was read. Due refreshes that turned
+    /// into no-ops, e.g. because the source is frozen, don't count.
+    fn refresh_if_due(&self) -> Result<bool, RefreshFileSourceError<E>> {
+        if !self.needs_refresh() || self.retry_throttled() {
+            return Ok(false);
+        }
+
+        let refreshes = self.stats().refreshes;
+        self.record_refresh_attempt(self.refresh_value())?;
+        Ok(self.stats().refreshes != refreshes)
+    }
+
+    /// Whether the last refresh failed less than the minimum retry interval ago.
+    fn retry_throttled(&self) -> bool {
+        let last_failed_refresh = *self
+            .last_failed_refresh
+            .read()
+            .expect("error reading last_failed_refresh in FileSource");
+        retry_throttled(
+            last_failed_refresh,
+            self.min_retry_interval,
+            self.clock.now(),
+        )
+    }
+
+    fn record_refresh_attempt(
+        &self,
+        result: Result<(), RefreshFileSourceError<E>>,
+    ) -> Result<(), RefreshFileSourceError<E>> {
+        if self.min_retry_interval.is_some() {
+            *self
+                .last_failed_refresh
+                .write()
+                .expect("error locking last_failed_refresh in FileSource") =
+                result.is_err().then(|| self.clock.now());
+        }
+        result
+    }
+
+    /// Refreshes on timeout unless a value is cached and less than the deadline threshold is
+    /// left until `deadline`, in which case the stale value is kept.
+    fn refresh_before_deadline(&self, deadline: Instant) -> Result<(), RefreshFileSourceError<E>> {
+        let remaining = deadline.saturating_duration_since(self.clock.now());
+        if self.last_refresh().is_some() && remaining < self.deadline_threshold {
+            return Ok(());
+        }
+
+        self.refresh_on_timeout()
+    }
+
+    /// Refreshes ahead of time once the prefetch ratio of the refresh interval has elapsed,
+    /// so a later [`ValueSource::value`] finds a warm cache.
+    pub fn maybe_prefetch(&self) -> Result<(), RefreshFileSourceError<E>> {
+        let (Some(ratio), Some(refresh_interval)) = (self.prefetch_ratio, self.refresh_interval)
+        else {
+            return self.refresh_on_timeout();
+        };
+        if self.last_refresh().is_none_or(|last_refresh| {
+            (last_refresh + refresh_interval.mul_f64(ratio)) <= self.clock.now()
         }) {
             self.refresh_value()?;
         }
@@ -83,112 +901,1337 @@ impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool>
     }
 
     pub fn refresh_value(&self) -> Result<(), RefreshFileSourceError<E>> {
-        if !self.filepath.exists() {
-            if REQUIRED {
-                return Err(RefreshFileSourceError::NoValue);
-            } else {
-                self.set_value(None);
-                return Ok(());
-            }
+        if self.refresh_paused() {
+            return Ok(());
         }
 
-        let mut file = File::open(&self.filepath)?;
-        let mut read_buf = String::with_capacity(INITIAL_READ_BUFFER_CAPACITY);
-        let _read_bytes = file.read_to_string(&mut read_buf)?;
+        let started = self.clock.now();
+        let mut record = ReadRecord::default();
+        let read = self.read_value(&mut record);
+        let committed = self.commit(read, record);
+        self.finish_refresh(started, committed)
+    }
 
-        let to_parse = if self.auto_trim {
-            read_buf.trim()
-        } else {
-            read_buf.as_str()
+    /// Counts and reports a committed refresh, including a parse error hidden by
+    /// [`FileSource::set_keep_last_good`], and returns its result.
+    fn finish_refresh(
+        &self,
+        started: Instant,
+        committed: Result<Option<Arc<RefreshFileSourceError<E>>>, RefreshFileSourceError<E>>,
+    ) -> Result<(), RefreshFileSourceError<E>> {
+        let error = match &committed {
+            Ok(hidden) => hidden.as_deref(),
+            Err(e) => Some(e),
         };
+        self.refresh_counters.record(error.is_none());
+        self.observe_refresh(started, error);
+        committed.map(|_| ())
+    }
 
-        let parsed = to_parse
-            .parse::<T>()
-            .map_err(|e| RefreshFileSourceError::ParseError(e))?;
+    /// Whether refreshes are no-ops because the source is frozen, waiting for its ready marker
+    /// or outside its refresh window. Never after an eviction, until the value is restored.
+    fn refresh_paused(&self) -> bool {
+        !self.evicted.load(Ordering::Acquire)
+            && (self.is_frozen() || self.awaiting_ready_marker() || self.outside_refresh_window())
+    }
 
-        self.set_value(Some(parsed));
-        Ok(())
+    fn is_frozen(&self) -> bool {
+        matches!(
+            *self
+                .freeze
+                .read()
+                .expect("error reading freeze state in FileSource"),
+            Freeze::Frozen
+        )
     }
-}
 
-#[derive(thiserror::Error, Debug)]
-pub enum ValueError<E: std::fmt::Debug> {
-    #[error("no value given for required config variable")]
-    NoValue,
-    #[error("error refreshing values: {0}")]
-    RefreshFileSourceError(#[from] RefreshFileSourceError<E>),
-}
+    /// Reads and parses the file like a refresh would, but discards the result: the cached value,
+    /// last refresh time, last error and everything recorded about the file are left untouched.
+    /// Meant for pre-flight config checks.
+    pub fn validate(&self) -> Result<(), RefreshFileSourceError<E>> {
+        match self.read_value(&mut ReadRecord::dry_run())? {
+            ValueOutcome::Loaded(_) => Ok(()),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing if REQUIRED => {
+                Err(RefreshFileSourceError::NoValue)
+            }
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => Ok(()),
+        }
+    }
 
-impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<T, E> for FileSource<T, true> {
-    fn value(&self) -> Result<T, ValueError<E>> {
-        self.refresh_on_timeout()?;
-        Ok(self
-            .value
-            .read()
-            .expect("error reading value in FileSource")
-            .to_owned()
-            .ok_or(ValueError::NoValue)?)
+    /// Stores the outcome of a completed read along with its record, or records its error.
+    /// Returns the parse error hidden by [`FileSource::set_keep_last_good`], if any.
+    fn commit(
+        &self,
+        read: Result<ValueOutcome<T>, RefreshFileSourceError<E>>,
+        record: ReadRecord,
+    ) -> Result<Option<Arc<RefreshFileSourceError<E>>>, RefreshFileSourceError<E>> {
+        self.apply_record(&record);
+        match read {
+            Ok(outcome) => {
+                self.set_value(outcome, record.fingerprint)?;
+                self.store_read_mark(record.mark);
+                self.set_last_error(None);
+                Ok(None)
+            }
+            Err(RefreshFileSourceError::ParseError(e))
+                if self.keep_last_good && matches!(self.cached(), ValueOutcome::Loaded(_)) =>
+            {
+                let error = Arc::new(RefreshFileSourceError::ParseError(e));
+                self.set_last_error(Some(error.clone()));
+                self.touch_last_refresh();
+                Ok(Some(error))
+            }
+            Err(e) => Err(e),
+        }
     }
-}
 
-impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<Option<T>, E>
-    for FileSource<T, false>
-{
-    fn value(&self) -> Result<Option<T>, ValueError<E>> {
-        self.refresh_on_timeout()?;
-        Ok(self
-            .value
-            .read()
-            .expect("error reading value in FileSource")
-            .to_owned())
+    fn read_value(
+        &self,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        if let Some(cache) = &self.shared_read_cache {
+            return self.read_value_shared(cache, record);
+        }
+        if let Some(timeout) = self.read_timeout {
+            return self.read_value_with_timeout(timeout, record);
+        }
+
+        let location = self.location();
+        let (mut file, metadata) = match self.backend.open_path(&location.path())? {
+            None => return self.missing(record),
+            Some(Fetched::Bytes { contents, modified }) => {
+                self.record_read(record, None, modified);
+                return self.parse_bytes(&contents, record);
+            }
+            Some(Fetched::File { file, path }) => {
+                let metadata = file.metadata()?;
+                self.verify_allowed_root(&path, &metadata)?;
+                (file, metadata)
+            }
+        };
+        self.record_read(record, Some(&metadata), metadata.modified().ok());
+        if !record.dry_run
+            && let Some(cached) = self.cached_if_unchanged(&metadata)
+        {
+            return Ok(cached);
+        }
+        #[cfg(feature = "memmap2")]
+        if self.mmap && !record.dry_run {
+            return self.parse_mapped(file, &metadata, record);
+        }
+
+        let mut read_buf = self
+            .read_buf
+            .lock()
+            .expect("error locking read buffer in FileSource");
+        read_buf.clear();
+        read_buf.reserve(metadata.len().try_into().unwrap_or(0));
+        location.rewind(&mut file)?;
+        file.read_to_end(&mut read_buf)?;
+
+        self.parse_bytes(&read_buf, record)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::io::Write;
+    /// Decompresses and decodes the raw bytes read from the file as configured, then parses
+    /// them. Shared by all ways of reading a file.
+    pub(crate) fn parse_bytes(
+        &self,
+        bytes: &[u8],
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        record.bytes = Some(bytes.len());
+        let contents = self.decode_bytes(bytes)?;
+        self.parse_contents(&contents, record)
+    }
 
-    use super::*;
+    /// Verifies, decompresses and decodes the raw bytes read from the file as configured.
+    fn decode_bytes<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, RefreshFileSourceError<E>> {
+        #[cfg(feature = "checksum")]
+        self.verify_checksum(bytes)?;
+        #[cfg(feature = "gzip")]
+        if let Cow::Owned(decompressed) = self.gunzip_sniffed(bytes)? {
+            return Ok(Cow::Owned(self.decode_text(&decompressed)?.into_owned()));
+        }
+        self.decode_text(bytes)
+    }
 
-    #[test]
-    fn simple_required() {
-        let source: FileSource<String, true> =
-            FileSource::from_path("sources/test-required".into());
+    fn decode_text<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, RefreshFileSourceError<E>> {
+        #[cfg(feature = "encoding_rs")]
+        return self.decode(bytes);
+        #[cfg(not(feature = "encoding_rs"))]
+        Ok(Cow::Borrowed(decode_utf8(bytes)?))
+    }
 
-        assert_eq!(source.value().unwrap(), "hello world!");
+    /// Reads the file into the reused read buffer and calls `f` with the contents, decoded and
+    /// preprocessed like a refresh would before parsing, without caching or recording anything.
+    /// `None` if the file is absent.
+    fn read_contents_with<R>(
+        &self,
+        f: impl FnOnce(&str) -> R,
+    ) -> Result<Option<R>, RefreshFileSourceError<E>> {
+        let mut read_buf = self
+            .read_buf
+            .lock()
+            .expect("error locking read buffer in FileSource");
+        let location = self.location();
+        match self.backend.open_path(&location.path())? {
+            None => return Ok(None),
+            Some(Fetched::Bytes { contents, .. }) => *read_buf = contents,
+            Some(Fetched::File { mut file, path }) => {
+                self.verify_allowed_root(&path, &file.metadata()?)?;
+                read_buf.clear();
+                location.rewind(&mut file)?;
+                file.read_to_end(&mut read_buf)?;
+            }
+        }
+
+        let decoded = self.decode_bytes(&read_buf)?;
+        let contents = self.preprocess(&decoded)?;
+        Ok(Some(f(self.trimmed(&contents))))
     }
 
-    #[test]
-    fn simple_optional_given() {
-        let source: FileSource<String, false> =
-            FileSource::from_path("sources/test-optional".into());
+    /// Outcome of reading a file that does not exist.
+    fn missing(
+        &self,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        record.present = Some(false);
+        if REQUIRED {
+            Err(RefreshFileSourceError::NoValue)
+        } else {
+            Ok(ValueOutcome::FileAbsent)
+        }
+    }
 
-        assert_eq!(
-            source.value().unwrap(),
-            Some("hello optional world!".to_owned())
-        );
+    /// Applies the configured preprocessing to decoded contents, up to trimming.
+    fn preprocess<'a>(&self, contents: &'a str) -> Result<Cow<'a, str>, RefreshFileSourceError<E>> {
+        let contents = if self.strip_bom {
+            contents.strip_prefix('\u{FEFF}').unwrap_or(contents)
+        } else {
+            contents
+        };
+        // `nth` stops at the first line past the limit instead of counting a runaway file.
+        if let Some(max_lines) = self.max_lines
+            && contents.lines().nth(max_lines).is_some()
+        {
+            return Err(RefreshFileSourceError::TooManyLines(max_lines));
+        }
+        let mut contents = Cow::Borrowed(contents);
+        if self.normalize_newlines && contents.contains('\r') {
+            contents = Cow::Owned(contents.replace("\r\n", "\n").replace('\r', "\n"));
+        }
+        if let Some(comment) = self.inline_comment {
+            contents = Cow::Owned(strip_inline_comments(&contents, comment));
+        }
+        if self.env_interpolation
+            && let Cow::Owned(interpolated) =
+                interpolate::interpolate_env(&contents, self.env_lookup.as_ref())?
+        {
+            contents = Cow::Owned(interpolated);
+        }
+        Ok(contents)
     }
 
-    #[test]
-    fn simple_optional_missing() {
-        let source: FileSource<String, false> =
-            FileSource::from_path("sources/test-optional-missing".into());
+    fn trimmed<'a>(&self, contents: &'a str) -> &'a str {
+        if self.auto_trim {
+            contents.trim()
+        } else {
+            contents
+        }
+    }
 
-        assert_eq!(source.value().unwrap(), None);
+    fn parse_contents(
+        &self,
+        contents: &str,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        let contents = self.preprocess(contents)?;
+        let to_parse = self.trimmed(&contents);
+
+        if let (Some(on_empty), true) = (self.on_empty, to_parse.is_empty()) {
+            match (on_empty, self.cached()) {
+                (OnEmpty::Error, _) => return Err(RefreshFileSourceError::EmptyValue),
+                (OnEmpty::KeepPrevious, previous @ ValueOutcome::Loaded(_)) => return Ok(previous),
+                _ if REQUIRED => return Err(RefreshFileSourceError::NoValue),
+                _ => return Ok(ValueOutcome::EmptyTreatedMissing),
+            }
+        }
+
+        record.fingerprint = Some(fingerprint(to_parse));
+        let parsed = to_parse
+            .parse::<T>()
+            .map_err(|e| RefreshFileSourceError::ParseError(e))?;
+        if let Some(validator) = &self.validator {
+            validator.validate(&parsed)?;
+        }
+
+        Ok(ValueOutcome::Loaded(parsed))
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone + PartialEq, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Calls `on_change` with the new value whenever a refresh loads a value that differs from
+    /// the previous one, by `PartialEq` unless a change comparator is set.
+    pub fn set_on_change(&mut self, on_change: ChangeCallback<T>) -> &mut Self
+    where
+        T: 'static,
+    {
+        self.set_on_change_with_info(Box::new(move |value, _| on_change(value)))
+    }
+
+    /// Like [`Self::set_on_change`], additionally passing [`ChangeInfo`] about the change.
+    pub fn set_on_change_with_info(&mut self, on_change: ChangeInfoCallback<T>) -> &mut Self
+    where
+        T: 'static,
+    {
+        self.on_change = Some(on_change);
+        self.change_comparator
+            .get_or_insert_with(|| Box::new(|old: &T, new: &T| old != new));
+        self
+    }
+
+    /// Refreshes and reports whether the file no longer holds `expected`, e.g. for
+    /// reconciliation loops checking what was last applied. A missing optional value counts as
+    /// drifted.
+    pub fn has_drifted_from(&self, expected: &T) -> Result<bool, ValueError<E>> {
+        self.refresh_value()?;
+        Ok(!matches!(self.cached(), ValueOutcome::Loaded(value) if value == *expected))
+    }
+
+    /// Like [`FileSource::freeze`], but refreshes keep reading the file and fail with
+    /// [`RefreshFileSourceError::FrozenValueChanged`] if its value differs from the pinned one.
+    pub fn freeze_strict(&self) {
+        *self
+            .freeze
+            .write()
+            .expect("error locking freeze state in FileSource") = Freeze::Strict(T::eq);
+    }
+}
+
+/// Never prints the cached value, only whether one is loaded, so sources holding secrets are
+/// safe to include in debug output.
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I> std::fmt::Debug
+    for FileSource<T, REQUIRED, I>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self.cached() {
+            ValueOutcome::Loaded(_) => "<loaded>",
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => "<empty>",
+        };
+        f.debug_struct("FileSource")
+            .field("path", &self.location().path())
+            .field("required", &REQUIRED)
+            .field("refresh_interval", &self.refresh_interval)
+            .field("value", &format_args!("{value}"))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E, T, const REQUIRED: bool, I> Refreshable for FileSource<T, REQUIRED, I>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    T: FromStr<Err = E> + Clone + Send + Sync,
+{
+    fn path(&self) -> PathBuf {
+        self.location().path()
+    }
+
+    fn refresh(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.refresh_value()?)
+    }
+
+    fn generation(&self) -> u64 {
+        FileSource::generation(self)
+    }
+}
+
+impl<const REQUIRED: bool, I> FileSource<String, REQUIRED, I> {
+    /// Parses the cached string into `U` without reading the file again.
+    pub fn parsed<U: FromStr>(&self) -> Result<U, ValueError<U::Err>>
+    where
+        U::Err: std::fmt::Debug,
+    {
+        match self.refreshed_raw()? {
+            ValueOutcome::Loaded(value) => value
+                .parse::<U>()
+                .map_err(|e| RefreshFileSourceError::ParseError(e).into()),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => {
+                Err(ValueError::NoValue)
+            }
+        }
+    }
+
+    /// Refreshes on timeout and returns the cached string, for sources parsing it themselves.
+    pub(crate) fn refreshed_raw<E: std::fmt::Debug>(
+        &self,
+    ) -> Result<ValueOutcome<String>, RefreshFileSourceError<E>> {
+        self.refresh_on_timeout()
+            .map_err(RefreshFileSourceError::widen)?;
+        Ok(self.cached())
+    }
+}
+
+/// Whether a value last refreshed at `last_refresh` is due at `now`, shared by every source with
+/// a refresh interval. A value never refreshed is always due, one without an interval never again.
+pub(crate) fn refresh_due(
+    last_refresh: Option<Instant>,
+    refresh_interval: Option<Duration>,
+    now: Instant,
+) -> bool {
+    last_refresh.is_none_or(|last_refresh| {
+        refresh_interval.is_some_and(|refresh_interval| (last_refresh + refresh_interval) < now)
+    })
+}
+
+/// Whether a refresh that failed at `last_failed_refresh` is less than `min_retry_interval` ago.
+pub(crate) fn retry_throttled(
+    last_failed_refresh: Option<Instant>,
+    min_retry_interval: Option<Duration>,
+    now: Instant,
+) -> bool {
+    match (min_retry_interval, last_failed_refresh) {
+        (Some(interval), Some(failed_at)) => now < failed_at + interval,
+        _ => false,
+    }
+}
+
+/// Hash telling contents apart, to notice changes without keeping a copy.
+pub(crate) fn fingerprint(contents: &str) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+    std::hash::Hash::hash(contents, &mut hasher);
+    std::hash::Hasher::finish(&hasher)
+}
+
+/// Borrows `bytes` as UTF-8, reporting invalid contents as an I/O error like `read_to_string`.
+pub(crate) fn decode_utf8(bytes: &[u8]) -> std::io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Removes everything from the first `comment` character to the end of each line.
+fn strip_inline_comments(contents: &str, comment: char) -> String {
+    contents
+        .lines()
+        .map(|line| line.split(comment).next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads and parses a file a single time, decoded and preprocessed like a default
+/// [`FileSource`] would, e.g. stripping a byte order mark, but without any of its caching.
+///
+/// Returns `Ok(None)` for a missing file unless `required` is set.
+pub fn read_once<T: FromStr>(
+    path: impl AsRef<std::path::Path>,
+    required: bool,
+    auto_trim: bool,
+) -> Result<Option<T>, RefreshFileSourceError<T::Err>>
+where
+    T::Err: std::fmt::Debug,
+{
+    let mut source = FileSource::<String, false>::from_path(path.as_ref().to_owned());
+    source.set_auto_trim(auto_trim);
+    match source
+        .read_contents_with(|contents| contents.parse::<T>())
+        .map_err(RefreshFileSourceError::widen)?
+    {
+        Some(parsed) => parsed.map(Some).map_err(RefreshFileSourceError::ParseError),
+        None if required => Err(RefreshFileSourceError::NoValue),
+        None => Ok(None),
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum ValueError<E: std::fmt::Debug> {
+    #[error("no value given for required config variable")]
+    NoValue,
+    #[error("error refreshing values: {0}")]
+    RefreshFileSourceError(#[from] RefreshFileSourceError<E>),
+    #[error("circuit breaker is open after repeated failures")]
+    CircuitOpen,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> ValueSource<T, E>
+    for FileSource<T, true, I>
+{
+    fn value(&self) -> Result<T, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        self.cached().into_option().ok_or(ValueError::NoValue)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, true, I> {
+    /// Like [`ValueSource::value`], but serves the cached value instead of refreshing if less
+    /// than the deadline threshold remains until `deadline`.
+    pub fn value_with_deadline(&self, deadline: Instant) -> Result<T, ValueError<E>> {
+        self.refresh_before_deadline(deadline)?;
+        self.cached().into_option().ok_or(ValueError::NoValue)
+    }
+
+    /// Like [`ValueSource::value`], also returning whether this call refreshed the value rather
+    /// than serving it from the cache, e.g. for cache hit ratio metrics.
+    pub fn value_with_stats(&self) -> Result<(T, bool), ValueError<E>> {
+        let refreshed = self.refresh_if_due()?;
+        let value = self.cached().into_option().ok_or(ValueError::NoValue)?;
+        Ok((value, refreshed))
+    }
+
+    /// Reads the file and hands the borrowed contents to `f`, decoded and preprocessed like a
+    /// refresh would before parsing, for parsers that can work on the input without an owned
+    /// copy. Bypasses the cache: nothing is stored, parsed or recorded.
+    pub fn with_contents<R>(
+        &self,
+        f: impl FnOnce(&str) -> R,
+    ) -> Result<R, RefreshFileSourceError<E>> {
+        self.read_contents_with(f)?
+            .ok_or(RefreshFileSourceError::NoValue)
+    }
+
+    /// Blocks until the file exists and parses, checking every `poll`, e.g. for a pod starting
+    /// before its volume is mounted. Returns the last error once `timeout` elapsed.
+    pub fn wait_until_ready(&self, timeout: Duration, poll: Duration) -> Result<T, ValueError<E>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.value() {
+                Ok(value) => return Ok(value),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => {
+                    std::thread::sleep(poll.min(deadline.saturating_duration_since(Instant::now())))
+                }
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> ValueSource<Option<T>, E>
+    for FileSource<T, false, I>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<E>> {
+        match self.value_or_error() {
+            Err(ValueError::RefreshFileSourceError(e)) if self.errors_are_none => {
+                self.set_last_error(Some(Arc::new(e)));
+                Ok(None)
+            }
+            value => value,
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, false, I> {
+    /// Return `Ok(None)` from [`ValueSource::value`] if refreshing fails, keeping the error in
+    /// [`FileSource::last_error`] instead.
+    pub fn set_errors_are_none(&mut self, errors_are_none: bool) -> &mut Self {
+        self.errors_are_none = errors_are_none;
+        self
+    }
+
+    /// Reads the file and hands the borrowed contents to `f`, decoded and preprocessed like a
+    /// refresh would before parsing, for parsers that can work on the input without an owned
+    /// copy. Bypasses the cache: nothing is stored, parsed or recorded. `None` if the file is
+    /// absent.
+    pub fn with_contents<R>(
+        &self,
+        f: impl FnOnce(&str) -> R,
+    ) -> Result<Option<R>, RefreshFileSourceError<E>> {
+        self.read_contents_with(f)
+    }
+
+    /// Like [`FileSource::value_or_error`], also returning whether this call refreshed the
+    /// value rather than serving it from the cache.
+    pub fn value_with_stats(&self) -> Result<(Option<T>, bool), ValueError<E>> {
+        let refreshed = self.refresh_if_due()?;
+        Ok((self.cached().into_option(), refreshed))
+    }
+
+    /// Like [`ValueSource::value`], but always returning refresh errors, regardless of
+    /// [`FileSource::set_errors_are_none`].
+    pub fn value_or_error(&self) -> Result<Option<T>, ValueError<E>> {
+        Ok(self.value_detailed()?.into_option())
+    }
+
+    /// Like [`ValueSource::value`], but reports why no value is present. Always returns
+    /// refresh errors.
+    pub fn value_detailed(&self) -> Result<ValueOutcome<T>, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        Ok(self.cached())
+    }
+
+    /// Like [`ValueSource::value`], but serves the cached value instead of refreshing if less
+    /// than the deadline threshold remains until `deadline`.
+    pub fn value_with_deadline(&self, deadline: Instant) -> Result<Option<T>, ValueError<E>> {
+        self.refresh_before_deadline(deadline)?;
+        Ok(self.cached().into_option())
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Copy, I> FileSource<T, true, I> {
+    /// Like [`ValueSource::value`], copying the value out under the read lock instead of
+    /// cloning the cached outcome.
+    pub fn get_copy(&self) -> Result<T, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        match *self
+            .value
+            .read()
+            .expect("error reading value in FileSource")
+        {
+            ValueOutcome::Loaded(value) => Ok(value),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => {
+                Err(ValueError::NoValue)
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Copy, I> FileSource<T, false, I> {
+    /// Like [`FileSource::value_or_error`], copying the value out under the read lock instead
+    /// of cloning the cached outcome.
+    pub fn get_copy(&self) -> Result<Option<T>, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        match *self
+            .value
+            .read()
+            .expect("error reading value in FileSource")
+        {
+            ValueOutcome::Loaded(value) => Ok(Some(value)),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::Write};
+
+    use super::*;
+
+    #[test]
+    fn simple_required() {
+        let source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+
+        assert_eq!(source.value().unwrap(), "hello world!");
+    }
+
+    #[test]
+    fn simple_optional_given() {
+        let source: FileSource<String, false> =
+            FileSource::from_path("sources/test-optional".into());
+
+        assert_eq!(
+            source.value().unwrap(),
+            Some("hello optional world!".to_owned())
+        );
+    }
+
+    #[test]
+    fn simple_optional_missing() {
+        let source: FileSource<String, false> =
+            FileSource::from_path("sources/test-optional-missing".into());
+
+        assert_eq!(source.value().unwrap(), None);
+    }
+
+    #[test]
+    fn required_missing() {
+        let source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required-missing".into());
+
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+    }
+
+    #[test]
+    fn open_error_not_treated_as_missing() {
+        let required: FileSource<String, true> =
+            FileSource::from_path("sources/test-required/not-a-dir".into());
+        assert!(matches!(
+            required.refresh_value(),
+            Err(RefreshFileSourceError::IOError(_))
+        ));
+
+        let optional: FileSource<String, false> =
+            FileSource::from_path("sources/test-required/not-a-dir".into());
+        assert!(matches!(
+            optional.refresh_value(),
+            Err(RefreshFileSourceError::IOError(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        std::fs::write(&file_path, "secret").unwrap();
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions don't apply to root, so there is nothing to check in that case.
+        let readable = File::open(&file_path).is_ok();
+        let source: FileSource<String, false> = FileSource::from_path(file_path.clone());
+        let result = source.refresh_value();
+
+        if !readable {
+            match result {
+                Err(RefreshFileSourceError::IOError(e)) => {
+                    assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied)
+                }
+                other => panic!("expected permission denied, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_leaves_cache() {
+        let source: TestSource<u16, true> = TestSource::new("8080");
+        source.refresh_value().unwrap();
+        let last_refresh = source.last_refresh();
+
+        source.write("909");
+        source.validate().unwrap();
+        assert_eq!(source.last_read_bytes(), Some(4));
+        source.write("not a port");
+        assert!(matches!(
+            source.validate(),
+            Err(RefreshFileSourceError::ParseError(_))
+        ));
+
+        assert_eq!(source.cached(), ValueOutcome::Loaded(8080));
+        assert_eq!(source.last_refresh(), last_refresh);
+        assert!(source.last_error().is_none());
+
+        let mut empty: FileSource<String, true> =
+            FileSource::from_path("sources/test-empty".into());
+        empty.set_empty_as_missing(true);
+        assert!(matches!(
+            empty.validate(),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+        assert_eq!(empty.last_refresh(), None);
+    }
+
+    #[test]
+    fn on_empty_policies() {
+        let source = |on_empty| {
+            let mut source: TestSource<u16, false> = TestSource::new("8080");
+            source.set_on_empty(Some(on_empty));
+            source.refresh_value().unwrap();
+            source.write("\n");
+            source
+        };
+
+        let error = source(OnEmpty::Error);
+        assert!(matches!(
+            error.refresh_value(),
+            Err(RefreshFileSourceError::EmptyValue)
+        ));
+        assert_eq!(error.cached(), ValueOutcome::Loaded(8080));
+
+        let missing = source(OnEmpty::TreatAsMissing);
+        missing.refresh_value().unwrap();
+        assert_eq!(missing.cached(), ValueOutcome::EmptyTreatedMissing);
+
+        let keep = source(OnEmpty::KeepPrevious);
+        keep.refresh_value().unwrap();
+        assert_eq!(keep.cached(), ValueOutcome::Loaded(8080));
+        keep.write("9090");
+        keep.refresh_value().unwrap();
+        assert_eq!(keep.cached(), ValueOutcome::Loaded(9090));
+    }
+
+    #[test]
+    fn switch_path() {
+        let mut source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        source.set_refresh_interval(Some(Duration::from_secs(3600)));
+        assert_eq!(source.value().unwrap(), "hello world!");
+
+        source.set_path("sources/test-port".into());
+        assert_eq!(
+            source.cached(),
+            ValueOutcome::Loaded("hello world!".to_owned())
+        );
+        assert_eq!(source.value().unwrap(), "8080");
+        assert_eq!(
+            Refreshable::path(&source),
+            PathBuf::from("sources/test-port")
+        );
+    }
+
+    #[test]
+    fn drift_detected() {
+        let source: TestSource<u16, false> = TestSource::new("8080");
+        assert!(!source.has_drifted_from(&8080).unwrap());
+
+        source.write("9090");
+        assert!(source.has_drifted_from(&8080).unwrap());
+        assert!(!source.has_drifted_from(&9090).unwrap());
+
+        source.remove();
+        assert!(source.has_drifted_from(&9090).unwrap());
+    }
+
+    #[test]
+    fn wait_until_ready_polls() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        let source: FileSource<u16, true> = FileSource::from_path(file_path.clone());
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            std::fs::write(&file_path, "8080").unwrap();
+        });
+
+        let value = source.wait_until_ready(Duration::from_secs(5), Duration::from_millis(10));
+        writer.join().unwrap();
+        assert_eq!(value.unwrap(), 8080);
+
+        let missing: FileSource<u16, true> =
+            FileSource::from_path("sources/test-required-missing".into());
+        assert!(matches!(
+            missing.wait_until_ready(Duration::from_millis(30), Duration::from_millis(10)),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::NoValue
+            ))
+        ));
+    }
+
+    #[test]
+    fn ready_marker_gates_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        let marker = dir.path().join("value.ready");
+        std::fs::write(&file_path, "first").unwrap();
+        let mut source: FileSource<String, false> = FileSource::from_path(file_path.clone());
+        source.set_ready_marker(Some(marker.clone()));
+        assert_eq!(source.value().unwrap(), None);
+
+        std::fs::write(&marker, "").unwrap();
+        assert_eq!(source.value().unwrap(), Some("first".to_owned()));
+
+        std::fs::remove_file(&marker).unwrap();
+        std::fs::write(&file_path, "half-written").unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), Some("first".to_owned()));
+        std::fs::write(&marker, "").unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), Some("half-written".to_owned()));
+    }
+
+    #[test]
+    fn errors_are_none() {
+        let mut source: FileSource<u16, false> =
+            FileSource::from_path("sources/test-required".into());
+        source.set_errors_are_none(true);
+
+        assert_eq!(source.value().unwrap(), None);
+        assert!(matches!(
+            source.last_error().as_deref(),
+            Some(RefreshFileSourceError::ParseError(_))
+        ));
+        assert!(matches!(
+            source.value_or_error(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(_)
+            ))
+        ));
+        assert_eq!(source.value().unwrap(), None);
+    }
+
+    #[test]
+    fn failed_refreshes_throttled() {
+        #[derive(Default)]
+        struct CountingOpener(std::sync::atomic::AtomicUsize);
+
+        impl Opener for CountingOpener {
+            fn open(&self, path: &std::path::Path) -> std::io::Result<File> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                File::open(path)
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        let opener = Arc::new(CountingOpener::default());
+        let clock = MockClock::new();
+        let mut source: FileSource<String, true> = FileSource::from_path(file_path.clone());
+        source
+            .set_opener(opener.clone())
+            .set_clock(Arc::new(clock.clone()))
+            .set_min_retry_interval(Some(Duration::from_secs(1)));
+
+        for _ in 0..5 {
+            assert!(source.value().is_err());
+        }
+        assert_eq!(opener.0.load(Ordering::Relaxed), 1);
+
+        std::fs::write(&file_path, "found").unwrap();
+        assert!(source.value().is_err());
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(source.value().unwrap(), "found");
+        assert_eq!(opener.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn get_copy_values() {
+        let port: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        assert_eq!(port.get_copy().unwrap(), 8080);
+
+        let missing: FileSource<u16, false> =
+            FileSource::from_path("sources/test-optional-missing".into());
+        assert_eq!(missing.get_copy().unwrap(), None);
+    }
+
+    #[test]
+    fn value_with_stats_reports_refresh() {
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_refresh_interval(Some(Duration::from_secs(60)));
+
+        assert_eq!(source.value_with_stats().unwrap(), (8080, true));
+        assert_eq!(source.value_with_stats().unwrap(), (8080, false));
+    }
+
+    #[test]
+    fn value_with_stats_frozen_not_refreshed() {
+        let mut source: TestSource<u16, true> = TestSource::new("8080");
+        source.set_refresh_interval(Some(Duration::from_secs(60)));
+        assert_eq!(source.value_with_stats().unwrap(), (8080, true));
+
+        source.freeze();
+        source.advance(Duration::from_secs(61));
+        assert_eq!(source.value_with_stats().unwrap(), (8080, false));
+    }
+
+    #[test]
+    fn multibyte_preprocessing() {
+        let mut source: TestSource<String, true> =
+            TestSource::new("\u{FEFF}\u{FEFF}ä€\r\n𝄞 # ü\r\nß");
+        source
+            .set_normalize_newlines(true)
+            .set_strip_inline_comment(Some('#'))
+            .set_max_lines(Some(3));
+
+        assert_eq!(source.value().unwrap(), "\u{FEFF}ä€\n𝄞 \nß");
+    }
+
+    #[test]
+    fn with_contents_borrows() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+
+        fn hash(contents: &str) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            contents.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let required: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        assert_eq!(required.with_contents(hash).unwrap(), hash("hello world!"));
+        assert_eq!(required.cached(), ValueOutcome::FileAbsent);
+
+        let optional: FileSource<String, false> =
+            FileSource::from_path("sources/test-optional-missing".into());
+        assert_eq!(optional.with_contents(hash).unwrap(), None);
+        let missing: FileSource<String, true> =
+            FileSource::from_path("sources/test-required-missing".into());
+        assert!(matches!(
+            missing.with_contents(hash),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+    }
+
+    #[test]
+    fn with_contents_preprocesses() {
+        let mut source: TestSource<u16, true> = TestSource::new("\u{FEFF}8080 # port\n");
+        source.set_strip_inline_comment(Some('#'));
+        assert_eq!(source.with_contents(str::to_owned).unwrap(), "8080");
+
+        source.set_max_lines(Some(0));
+        assert!(matches!(
+            source.with_contents(str::len),
+            Err(RefreshFileSourceError::TooManyLines(0))
+        ));
+        assert_eq!(source.last_read_bytes(), None);
+    }
+
+    #[test]
+    fn detailed_absent_vs_empty() {
+        let absent: FileSource<String, false> =
+            FileSource::from_path("sources/test-optional-missing".into());
+        assert_eq!(absent.value_detailed().unwrap(), ValueOutcome::FileAbsent);
+
+        let mut empty: FileSource<String, false> =
+            FileSource::from_path("sources/test-empty".into());
+        empty.set_empty_as_missing(true);
+        assert_eq!(
+            empty.value_detailed().unwrap(),
+            ValueOutcome::EmptyTreatedMissing
+        );
+        assert_eq!(empty.value().unwrap(), None);
+    }
+
+    #[test]
+    fn parsed_from_cached_string() {
+        let source: FileSource<String, true> = FileSource::from_path("sources/test-port".into());
+
+        assert_eq!(source.value().unwrap(), "8080");
+        assert_eq!(source.parsed::<u16>().unwrap(), 8080);
+        assert!(matches!(
+            source.parsed::<bool>(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn prefetch_before_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"first")
+            .unwrap();
+        let clock = MockClock::new();
+        let mut source: FileSource<String, true> = FileSource::from_path(file_path.clone());
+        source
+            .set_refresh_interval(Some(Duration::from_secs(10)))
+            .set_prefetch_ratio(0.8)
+            .set_clock(Arc::new(clock.clone()));
+        assert_eq!(source.value().unwrap(), "first");
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"second")
+            .unwrap();
+        clock.advance(Duration::from_secs(7));
+        source.maybe_prefetch().unwrap();
+        assert_eq!(source.value().unwrap(), "first");
+
+        clock.advance(Duration::from_secs(1));
+        source.maybe_prefetch().unwrap();
+        assert_eq!(source.value().unwrap(), "second");
+    }
+
+    #[test]
+    fn deadline_serves_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"first")
+            .unwrap();
+        let clock = MockClock::new();
+        let mut source: FileSource<String, true> = FileSource::from_path(file_path.clone());
+        source
+            .set_refresh_interval(Some(Duration::from_secs(5)))
+            .set_deadline_threshold(Duration::from_millis(10))
+            .set_clock(Arc::new(clock.clone()));
+        assert_eq!(source.value().unwrap(), "first");
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"second")
+            .unwrap();
+        clock.advance(Duration::from_secs(6));
+        let near_deadline = clock.now() + Duration::from_millis(1);
+        assert_eq!(source.value_with_deadline(near_deadline).unwrap(), "first");
+
+        let far_deadline = clock.now() + Duration::from_secs(1);
+        assert_eq!(source.value_with_deadline(far_deadline).unwrap(), "second");
+    }
+
+    #[test]
+    fn frozen_value_stays() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"first")
+            .unwrap();
+        let source: FileSource<String, true> = FileSource::from_path(file_path.clone());
+        assert_eq!(source.value().unwrap(), "first");
+        source.freeze();
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"second")
+            .unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), "first");
+    }
+
+    #[test]
+    fn strict_frozen_value_errors_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"first")
+            .unwrap();
+        let source: FileSource<String, true> = FileSource::from_path(file_path.clone());
+        assert_eq!(source.value().unwrap(), "first");
+        source.freeze_strict();
+        source.refresh_value().unwrap();
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"second")
+            .unwrap();
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::FrozenValueChanged)
+        ));
+        assert_eq!(source.value().unwrap(), "first");
+    }
+
+    #[test]
+    fn change_comparator_suppresses_callback() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path).unwrap().write_all(b"1.0").unwrap();
+        let changes = Arc::new(AtomicUsize::new(0));
+        let mut source: FileSource<f64, true> = FileSource::from_path(file_path.clone());
+        let callback_changes = changes.clone();
+        source
+            .set_on_change(Box::new(move |_| {
+                callback_changes.fetch_add(1, Ordering::SeqCst);
+            }))
+            .set_change_comparator(Box::new(|old, new| (old - new).abs() > 1e-6));
+        assert_eq!(source.value().unwrap(), 1.0);
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"1.0000000001")
+            .unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 0);
+
+        File::create(&file_path).unwrap().write_all(b"2.0").unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn read_buffer_reused() {
+        let source: FileSource<String, false> =
+            FileSource::from_path("sources/test-optional".into());
+        source.refresh_value().unwrap();
+        let capacity = source.read_buf.lock().unwrap().capacity();
+        let pointer = source.read_buf.lock().unwrap().as_ptr();
+        assert!(capacity >= "hello optional world!".len());
+
+        source.refresh_value().unwrap();
+        assert_eq!(source.read_buf.lock().unwrap().capacity(), capacity);
+        assert_eq!(source.read_buf.lock().unwrap().as_ptr(), pointer);
+    }
+
+    #[test]
+    fn keep_last_good_on_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"8080")
+            .unwrap();
+        let mut source: FileSource<u16, false> = FileSource::from_path(file_path.clone());
+        source.set_keep_last_good(true);
+        assert_eq!(source.value().unwrap(), Some(8080));
+        assert!(source.last_error().is_none());
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"not a port")
+            .unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), Some(8080));
+        assert!(matches!(
+            source.last_error().as_deref(),
+            Some(RefreshFileSourceError::ParseError(_))
+        ));
+        assert_eq!(source.stats().errors, 1);
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"9090")
+            .unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), Some(9090));
+        assert!(source.last_error().is_none());
+    }
+
+    #[test]
+    fn evicted_value_restored_despite_freeze() {
+        let source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        assert_eq!(source.value().unwrap(), 8080);
+        source.freeze();
+        assert!(!source.evict());
+        assert_eq!(source.cached(), ValueOutcome::Loaded(8080));
+
+        let source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        assert_eq!(source.value().unwrap(), 8080);
+        assert!(source.evict());
+        source.freeze();
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[test]
+    fn read_once_without_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"8080\n")
+            .unwrap();
+        assert_eq!(
+            read_once::<u16>(&file_path, true, true).unwrap(),
+            Some(8080)
+        );
+
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"9090\n")
+            .unwrap();
+        assert_eq!(
+            read_once::<u16>(&file_path, true, true).unwrap(),
+            Some(9090)
+        );
+        assert!(matches!(
+            read_once::<u16>(&file_path, true, false),
+            Err(RefreshFileSourceError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn read_once_preprocesses_like_source() {
+        let source: TestSource<u16, true> = TestSource::new("\u{FEFF}8080\n");
+
+        assert_eq!(
+            read_once::<u16>(source.path(), true, true).unwrap(),
+            Some(8080)
+        );
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[test]
+    fn read_once_missing() {
+        assert_eq!(
+            read_once::<String>("sources/test-optional-missing", false, true).unwrap(),
+            None
+        );
+        assert!(matches!(
+            read_once::<String>("sources/test-optional-missing", true, true),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+    }
+
+    #[test]
+    fn collect_values() {
+        let required: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        let port: FileSource<String, true> = FileSource::from_path("sources/test-port".into());
+        let missing: FileSource<String, true> =
+            FileSource::from_path("sources/test-optional-missing".into());
+        let sources: [&dyn ValueSource<String, Infallible>; 3] = [&required, &port, &missing];
+
+        let collected: Vec<_> = values(sources).collect();
+        assert_eq!(collected[0].as_ref().unwrap(), "hello world!");
+        assert_eq!(collected[1].as_ref().unwrap(), "8080");
+        assert!(collected[2].is_err());
+        assert!(
+            values([&required as &dyn ValueSource<_, _>, &port])
+                .collect::<Result<Vec<_>, _>>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn strip_inline_comment() {
+        let mut source: FileSource<u16, true> =
+            FileSource::from_path("sources/test-port-comment".into());
+        source.set_strip_inline_comment(Some('#'));
+        assert_eq!(source.value().unwrap(), 8080);
+
+        let mut unstripped: FileSource<String, true> =
+            FileSource::from_path("sources/test-port-comment".into());
+        unstripped
+            .set_strip_inline_comment(Some('#'))
+            .set_strip_inline_comment(None);
+        assert_eq!(unstripped.value().unwrap(), "8080 # app port");
+    }
+
+    #[test]
+    fn last_read_bytes() {
+        let source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        assert_eq!(source.last_read_bytes(), None);
+
+        source.refresh_value().unwrap();
+        assert_eq!(source.last_read_bytes(), Some("hello world!\n".len()));
+    }
+
+    #[test]
+    fn strip_bom() {
+        let mut source: FileSource<u16, true> =
+            FileSource::from_path("sources/test-port-bom".into());
+        assert_eq!(source.value().unwrap(), 8080);
+
+        source.set_strip_bom(false);
+        assert!(source.refresh_value().is_err());
+    }
+
+    #[test]
+    fn normalize_newlines() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Lines(Vec<String>);
+
+        impl FromStr for Lines {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(Lines(s.split('\n').map(str::to_owned).collect()))
+            }
+        }
+
+        let mut source: FileSource<Lines, true> = FileSource::from_path("sources/test-crlf".into());
+        source.set_normalize_newlines(true);
+        assert_eq!(
+            source.value().unwrap(),
+            Lines(vec!["first".into(), "second".into(), "third".into()])
+        );
+    }
+
+    #[test]
+    fn debug_hides_value() {
+        let source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        assert!(format!("{source:?}").contains("<empty>"));
+
+        source.refresh_value().unwrap();
+        let debug = format!("{source:?}");
+        assert!(debug.contains("sources/test-required"));
+        assert!(debug.contains("<loaded>"));
+        assert!(!debug.contains("hello world!"));
     }
 
     #[test]
     fn timeout_refresh() {
-        let file_path = "sources/refresh-overwrite";
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("value");
         {
-            let mut source_file = File::create(file_path).unwrap();
+            let mut source_file = File::create(&file_path).unwrap();
             source_file.write_all("first".as_bytes()).unwrap();
         }
-        let mut source: FileSource<String, false> = FileSource::from_path(file_path.into());
+        let mut source: FileSource<String, false> = FileSource::from_path(file_path.clone());
         source.set_refresh_interval(Some(Duration::from_secs(5)));
         assert_eq!(source.value().unwrap(), Some("first".to_owned()));
         {
-            let mut source_file = File::create(file_path).unwrap();
+            let mut source_file = File::create(&file_path).unwrap();
             source_file.write_all("second".as_bytes()).unwrap();
             source_file.flush().unwrap();
         }
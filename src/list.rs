@@ -0,0 +1,157 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome, ValueSource};
+
+const DEFAULT_SEPARATOR: char = ',';
+
+/// Source parsing a separated list (`a,b,c`) into a `Vec`.
+///
+/// Elements are trimmed and empty elements skipped. The list is parsed from the cached file
+/// contents on each access.
+pub struct ListFileSource<T: FromStr, const REQUIRED: bool> {
+    source: FileSource<String, REQUIRED>,
+    separator: char,
+    sort: Option<fn(&mut [T])>,
+    dedup: Option<fn(&mut Vec<T>)>,
+}
+
+impl<T: FromStr, const REQUIRED: bool> ListFileSource<T, REQUIRED>
+where
+    T::Err: std::fmt::Debug,
+{
+    pub fn new(source: FileSource<String, REQUIRED>) -> Self {
+        Self {
+            source,
+            separator: DEFAULT_SEPARATOR,
+            sort: None,
+            dedup: None,
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    pub fn set_separator(&mut self, separator: char) -> &mut Self {
+        self.separator = separator;
+        self
+    }
+
+    /// See [`FileSource::set_max_lines`].
+    pub fn set_max_lines(&mut self, max_lines: Option<usize>) -> &mut Self {
+        self.source.set_max_lines(max_lines);
+        self
+    }
+
+    fn parsed(&self) -> Result<Option<Vec<T>>, ValueError<T::Err>> {
+        let ValueOutcome::Loaded(contents) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+        let mut list = contents
+            .split(self.separator)
+            .map(str::trim)
+            .filter(|element| !element.is_empty())
+            .map(|element| element.parse().map_err(RefreshFileSourceError::ParseError))
+            .collect::<Result<Vec<T>, _>>()?;
+        if let Some(sort) = self.sort {
+            sort(&mut list);
+        }
+        if let Some(dedup) = self.dedup {
+            dedup(&mut list);
+        }
+
+        Ok(Some(list))
+    }
+}
+
+impl<T: FromStr + Ord, const REQUIRED: bool> ListFileSource<T, REQUIRED>
+where
+    T::Err: std::fmt::Debug,
+{
+    /// Sort the parsed elements, for stable downstream behavior regardless of file order.
+    pub fn set_sorted(&mut self, sorted: bool) -> &mut Self {
+        self.sort = sorted.then_some(<[T]>::sort as _);
+        self
+    }
+
+    /// Remove repeated elements, keeping the first occurrence.
+    pub fn set_dedup(&mut self, dedup: bool) -> &mut Self
+    where
+        T: Clone,
+    {
+        self.dedup = dedup.then_some(dedup_keep_first::<T> as _);
+        self
+    }
+}
+
+fn dedup_keep_first<T: Ord + Clone>(list: &mut Vec<T>) {
+    let mut seen = BTreeSet::new();
+    list.retain(|element| seen.insert(element.clone()));
+}
+
+impl<T: FromStr> ValueSource<Vec<T>, T::Err> for ListFileSource<T, true>
+where
+    T::Err: std::fmt::Debug,
+{
+    fn value(&self) -> Result<Vec<T>, ValueError<T::Err>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+/// Returns an empty list if the file is absent.
+impl<T: FromStr> ValueSource<Vec<T>, T::Err> for ListFileSource<T, false>
+where
+    T::Err: std::fmt::Debug,
+{
+    fn value(&self) -> Result<Vec<T>, ValueError<T::Err>> {
+        Ok(self.parsed()?.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list() {
+        let source: ListFileSource<String, true> =
+            ListFileSource::new(FileSource::from_path("sources/test-list".into()));
+
+        assert_eq!(source.value().unwrap(), ["b", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn sorted_and_dedup() {
+        let mut source: ListFileSource<String, true> =
+            ListFileSource::new(FileSource::from_path("sources/test-list".into()));
+        source.set_sorted(true).set_dedup(true);
+
+        assert_eq!(source.value().unwrap(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn too_many_lines() {
+        let lines_source = |max_lines| {
+            let mut source: ListFileSource<u16, true> =
+                ListFileSource::new(FileSource::from_path("sources/test-list-lines".into()));
+            source.set_separator('\n').set_max_lines(Some(max_lines));
+            source
+        };
+
+        assert_eq!(lines_source(5).value().unwrap(), [1, 2, 3, 4, 5]);
+        assert!(matches!(
+            lines_source(3).value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::TooManyLines(3)
+            ))
+        ));
+    }
+
+    #[test]
+    fn optional_missing_is_empty() {
+        let source: ListFileSource<u16, false> =
+            ListFileSource::new(FileSource::from_path("sources/test-list-missing".into()));
+
+        assert!(source.value().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fs::File, io, path::PathBuf};
+
+#[cfg(unix)]
+use std::{io::Seek, os::fd::RawFd};
+
+/// Where a source reads its contents from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Location {
+    Path(PathBuf),
+    /// An already open file descriptor, reopened through `/dev/fd` and read from the beginning
+    /// on every refresh. The source does not take ownership of the descriptor.
+    ///
+    /// On Linux the reopened file has an offset of its own. On macOS and the BSDs it is a
+    /// duplicate sharing the descriptor's offset, which each refresh moves, so the descriptor
+    /// should not be read elsewhere at the same time.
+    #[cfg(unix)]
+    Fd(RawFd),
+}
+
+impl Location {
+    pub fn path(&self) -> PathBuf {
+        match self {
+            Location::Path(path) => path.clone(),
+            #[cfg(unix)]
+            Location::Fd(fd) => PathBuf::from(format!("/dev/fd/{fd}")),
+        }
+    }
+
+    /// Seeks a file opened at [`Location::path`] back to the start if it may share its offset
+    /// with the descriptor, leaving unseekable files such as pipes as they are.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    pub(crate) fn rewind(&self, file: &mut File) -> io::Result<()> {
+        match self {
+            Location::Path(_) => Ok(()),
+            #[cfg(unix)]
+            Location::Fd(_) => match file.rewind() {
+                Err(e) if e.kind() != io::ErrorKind::NotSeekable => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+impl From<PathBuf> for Location {
+    fn from(path: PathBuf) -> Self {
+        Location::Path(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileSource, ValueSource};
+
+    #[test]
+    fn from_location_path() {
+        let source: FileSource<String, true> =
+            FileSource::from_location(Location::Path("sources/test-required".into()));
+
+        assert_eq!(source.value().unwrap(), "hello world!");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_location_fd() {
+        use std::{fs::File, os::fd::AsRawFd};
+
+        let mut file = File::open("sources/test-required").unwrap();
+        io::Read::read_exact(&mut file, &mut [0; 6]).unwrap();
+        let source: FileSource<String, true> =
+            FileSource::from_location(Location::Fd(file.as_raw_fd()));
+
+        assert_eq!(source.value().unwrap(), "hello world!");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), "hello world!");
+    }
+}
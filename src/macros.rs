@@ -0,0 +1,78 @@
+/// Declares a struct of [`FileSource`](crate::FileSource) fields together with a `new`
+/// constructor. Sources marked `(required)` are `FileSource<T, true>`, all others are optional.
+///
+/// ```no_run
+/// k8s_config::sources! {
+///     pub struct Config {
+///         DB_URL: String @ "/etc/config/DB_URL" (required),
+///         PORT: u16 @ "/etc/config/PORT",
+///     }
+/// }
+///
+/// let config = Config::new();
+/// ```
+#[macro_export]
+macro_rules! sources {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $field:ident : $($segment:ident)::+ $(<$($generic:ty),+>)?
+                    @ $path:literal $(($required:ident))?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(non_snake_case)]
+        $vis struct $name {
+            $(
+                pub $field: $crate::FileSource<
+                    $($segment)::+ $(<$($generic),+>)?,
+                    { $crate::sources!(@required $($required)?) },
+                >,
+            )*
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    $($field: $crate::FileSource::from_path($path.into()),)*
+                }
+            }
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+    (@required required) => {
+        true
+    };
+    (@required) => {
+        false
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValueSource;
+
+    crate::sources! {
+        struct TestSources {
+            GREETING: String @ "sources/test-required" (required),
+            PORT: u16 @ "sources/test-port",
+            ADDRESS: std::net::IpAddr @ "sources/test-optional-missing",
+        }
+    }
+
+    #[test]
+    fn declares_sources() {
+        let sources = TestSources::new();
+
+        assert_eq!(sources.GREETING.value().unwrap(), "hello world!");
+        assert_eq!(sources.PORT.value().unwrap(), Some(8080));
+        assert_eq!(sources.ADDRESS.value().unwrap(), None);
+    }
+}
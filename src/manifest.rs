@@ -0,0 +1,147 @@
+use std::{collections::HashMap, error::Error, path::PathBuf, str::FromStr, sync::Arc};
+
+use serde::Deserialize;
+
+use crate::{ConfigValue, DurationValue, ErasedSource, FileSource, SourceRegistry};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ManifestError {
+    #[error("error reading manifest: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("error parsing manifest: {0}")]
+    ParseError(#[from] toml::de::Error),
+    #[error("invalid refresh interval for source {0:?}: {1}")]
+    InvalidInterval(String, crate::DurationParseError),
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    sources: HashMap<String, ManifestEntry>,
+}
+
+#[derive(Deserialize)]
+struct ManifestEntry {
+    path: PathBuf,
+    #[serde(rename = "type")]
+    source_type: SourceType,
+    #[serde(default)]
+    required: bool,
+    interval: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum SourceType {
+    String,
+    Int,
+    Bool,
+    Duration,
+}
+
+impl SourceRegistry {
+    /// Builds a registry from a TOML manifest of sources:
+    ///
+    /// ```toml
+    /// [sources.db_url]
+    /// path = "/etc/config/DB_URL"
+    /// type = "string" # string, int, bool or duration
+    /// required = true
+    /// interval = "30s"
+    /// ```
+    pub fn from_manifest_str(manifest: &str) -> Result<Self, ManifestError> {
+        let manifest: Manifest = toml::from_str(manifest)?;
+        let mut registry = SourceRegistry::new();
+        for (name, entry) in manifest.sources {
+            let source = entry
+                .build()
+                .map_err(|e| ManifestError::InvalidInterval(name.clone(), e))?;
+            registry.insert(name, source);
+        }
+
+        Ok(registry)
+    }
+
+    pub fn from_manifest(path: impl AsRef<std::path::Path>) -> Result<Self, ManifestError> {
+        Self::from_manifest_str(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl ManifestEntry {
+    fn build(self) -> Result<Arc<dyn ErasedSource>, crate::DurationParseError> {
+        Ok(match (self.source_type, self.required) {
+            (SourceType::String, true) => self.build_typed::<String, true>()?,
+            (SourceType::String, false) => self.build_typed::<String, false>()?,
+            (SourceType::Int, true) => self.build_typed::<i64, true>()?,
+            (SourceType::Int, false) => self.build_typed::<i64, false>()?,
+            (SourceType::Bool, true) => self.build_typed::<bool, true>()?,
+            (SourceType::Bool, false) => self.build_typed::<bool, false>()?,
+            (SourceType::Duration, true) => self.build_typed::<DurationValue, true>()?,
+            (SourceType::Duration, false) => self.build_typed::<DurationValue, false>()?,
+        })
+    }
+
+    fn build_typed<T, const REQUIRED: bool>(
+        &self,
+    ) -> Result<Arc<dyn ErasedSource>, crate::DurationParseError>
+    where
+        T: FromStr + Clone + Into<ConfigValue> + Send + Sync + 'static,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        let interval = self
+            .interval
+            .as_deref()
+            .map(|interval| interval.parse::<DurationValue>().map(Into::into))
+            .transpose()?;
+        let mut source = FileSource::<T, REQUIRED>::from_path(self.path.clone());
+        source.set_refresh_interval(interval);
+        Ok(Arc::new(source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_entry_manifest() {
+        let registry = SourceRegistry::from_manifest_str(
+            r#"
+            [sources.greeting]
+            path = "sources/test-required"
+            type = "string"
+            required = true
+
+            [sources.port]
+            path = "sources/test-port"
+            type = "int"
+            interval = "30s"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.value("greeting").unwrap(),
+            Some(ConfigValue::String("hello world!".to_owned()))
+        );
+        assert_eq!(
+            registry.value("port").unwrap(),
+            Some(ConfigValue::Int(8080))
+        );
+    }
+
+    #[test]
+    fn invalid_interval() {
+        assert!(matches!(
+            SourceRegistry::from_manifest_str(
+                r#"
+                [sources.port]
+                path = "sources/test-port"
+                type = "int"
+                interval = "soon"
+                "#,
+            ),
+            Err(ManifestError::InvalidInterval(name, _)) if name == "port"
+        ));
+    }
+}
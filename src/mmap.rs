@@ -0,0 +1,103 @@
+use std::{
+    fs::{File, Metadata},
+    io::Read,
+    str::FromStr,
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    FileSource, RefreshFileSourceError, ValueOutcome, backend::ReadRecord, skip_unchanged::ReadMark,
+};
+
+pub(crate) struct MappedFile {
+    mark: ReadMark,
+    map: Mmap,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Memory-map the file and parse from the mapping, remapping only when its size or mtime
+    /// changes.
+    ///
+    /// The mapping stays valid when Kubernetes swaps the `..data` symlink, since the old file
+    /// is unlinked rather than modified. Truncating the mapped file in place is NOT safe and can
+    /// terminate the process with `SIGBUS`, so only use this for files replaced atomically.
+    /// If mapping fails (e.g. for empty files), the file is read normally instead.
+    pub fn set_mmap(&mut self, mmap: bool) -> &mut Self {
+        self.mmap = mmap;
+        if !mmap {
+            *self
+                .mapped
+                .write()
+                .expect("error locking mapped file in FileSource") = None;
+        }
+        self
+    }
+
+    pub(crate) fn parse_mapped(
+        &self,
+        mut file: File,
+        metadata: &Metadata,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        let mut mapped = self
+            .mapped
+            .write()
+            .expect("error locking mapped file in FileSource");
+
+        if mapped
+            .as_ref()
+            .is_none_or(|mapped| mapped.mark.changed(metadata))
+        {
+            // SAFETY: see `set_mmap`, the file must not be truncated while mapped.
+            match unsafe { Mmap::map(&file) } {
+                Ok(map) => {
+                    *mapped = Some(MappedFile {
+                        mark: ReadMark::from(metadata),
+                        map,
+                    })
+                }
+                Err(_) => {
+                    *mapped = None;
+                    let mut read_buf = Vec::new();
+                    file.read_to_end(&mut read_buf)?;
+                    return self.parse_bytes(&read_buf, record);
+                }
+            }
+        }
+
+        let map = &mapped.as_ref().expect("mapping was just created").map;
+        self.parse_bytes(map, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{TestSource, ValueSource};
+
+    use super::*;
+
+    #[test]
+    fn mmap_updates_on_mtime_change() {
+        let mut source: TestSource<String, true> = TestSource::new("first");
+        source.set_mmap(true);
+        assert_eq!(source.value().unwrap(), "first");
+        assert!(source.mapped.read().unwrap().is_some());
+
+        // Truncating the mapped file in place could raise SIGBUS, replace it like a volume
+        // update instead. Same size as before, so only the mtime tells the files apart.
+        source.replace("third");
+        File::options()
+            .write(true)
+            .open(source.path())
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), "third");
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome};
+
+type Parser<T> = Box<dyn Fn(&str) -> Result<T, String> + Send + Sync>;
+
+/// Error of every parser tried by [`MultiParse`], in order.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("no parser accepted the value: {}", .0.join("; "))]
+pub struct MultiParseError(pub Vec<String>);
+
+/// Ordered list of parsers for values accepting several formats, returning the result of the
+/// first one that succeeds. Used with [`FileSource::parsed_with`].
+pub struct MultiParse<T> {
+    parsers: Vec<Parser<T>>,
+}
+
+impl<T> Default for MultiParse<T> {
+    fn default() -> Self {
+        Self {
+            parsers: Vec::new(),
+        }
+    }
+}
+
+impl<T> MultiParse<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a parser, tried after all parsers added before it.
+    pub fn or<E: Display>(
+        mut self,
+        parser: impl Fn(&str) -> Result<T, E> + Send + Sync + 'static,
+    ) -> Self {
+        self.parsers
+            .push(Box::new(move |s| parser(s).map_err(|e| e.to_string())));
+        self
+    }
+
+    pub fn parse(&self, s: &str) -> Result<T, MultiParseError> {
+        let mut errors = Vec::with_capacity(self.parsers.len());
+        for parser in &self.parsers {
+            match parser(s) {
+                Ok(value) => return Ok(value),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Err(MultiParseError(errors))
+    }
+}
+
+impl<const REQUIRED: bool, I> FileSource<String, REQUIRED, I> {
+    /// Like [`FileSource::parsed`], but trying each parser of `parsers` in turn.
+    pub fn parsed_with<U>(
+        &self,
+        parsers: &MultiParse<U>,
+    ) -> Result<U, ValueError<MultiParseError>> {
+        match self.refreshed_raw()? {
+            ValueOutcome::Loaded(value) => parsers
+                .parse(&value)
+                .map_err(|e| RefreshFileSourceError::ParseError(e).into()),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => {
+                Err(ValueError::NoValue)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::DurationValue;
+
+    fn duration_parsers() -> MultiParse<Duration> {
+        MultiParse::new()
+            .or(|s| s.parse::<u64>().map(Duration::from_secs))
+            .or(|s| s.parse::<DurationValue>().map(Duration::from))
+    }
+
+    #[test]
+    fn first_success_wins() {
+        let parsers = duration_parsers();
+
+        assert_eq!(parsers.parse("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parsers.parse("500ms").unwrap(), Duration::from_millis(500));
+
+        let source: FileSource<String, true> = FileSource::from_path("sources/test-port".into());
+        assert_eq!(
+            source.parsed_with(&parsers).unwrap(),
+            Duration::from_secs(8080)
+        );
+    }
+
+    #[test]
+    fn all_failures_listed() {
+        let MultiParseError(errors) = duration_parsers().parse("soon").unwrap_err();
+
+        assert_eq!(
+            errors,
+            [
+                "invalid digit found in string",
+                "invalid duration: \"soon\""
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    fmt::Debug,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{FileSource, RefreshFileSourceError};
+
+/// A completed refresh attempt, as reported to a [`RefreshObserver`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct RefreshEvent<'a> {
+    pub path: &'a Path,
+    pub duration: Duration,
+    /// Error the refresh returned or, with [`FileSource::set_keep_last_good`], the parse error it
+    /// hid; `None` if it succeeded.
+    pub error: Option<&'a dyn Debug>,
+}
+
+/// Hook notified after every refresh of a source, e.g. to record metrics.
+pub trait RefreshObserver: Send + Sync {
+    fn on_refresh(&self, event: &RefreshEvent<'_>);
+}
+
+impl<E: Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I> FileSource<T, REQUIRED, I> {
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn RefreshObserver>>) -> &mut Self {
+        self.observer = observer;
+        self
+    }
+
+    pub(crate) fn observe_refresh(
+        &self,
+        started: Instant,
+        error: Option<&RefreshFileSourceError<E>>,
+    ) {
+        if let Some(observer) = &self.observer {
+            let path = self.location().path();
+            observer.on_refresh(&RefreshEvent {
+                path: &path,
+                duration: self.clock.now().saturating_duration_since(started),
+                error: error.map(|e| e as &dyn Debug),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    #[derive(Default)]
+    struct RecordingObserver(Mutex<Vec<(String, bool)>>);
+
+    impl RefreshObserver for RecordingObserver {
+        fn on_refresh(&self, event: &RefreshEvent<'_>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.path.display().to_string(), event.error.is_none()));
+        }
+    }
+
+    #[test]
+    fn observes_refreshes() {
+        let observer = Arc::new(RecordingObserver::default());
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_observer(Some(observer.clone()));
+        let mut broken: FileSource<u16, true> =
+            FileSource::from_path("sources/test-required".into());
+        broken.set_observer(Some(observer.clone()));
+
+        assert_eq!(source.value().unwrap(), 8080);
+        assert!(broken.value().is_err());
+        assert_eq!(
+            *observer.0.lock().unwrap(),
+            [
+                ("sources/test-port".to_owned(), true),
+                ("sources/test-required".to_owned(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn observes_parse_errors_kept_in_last_good() {
+        let observer = Arc::new(RecordingObserver::default());
+        let mut source: TestSource<u16, true> = TestSource::new("8080");
+        source
+            .set_keep_last_good(true)
+            .set_observer(Some(observer.clone()));
+        source.refresh_value().unwrap();
+
+        source.write("not a port");
+        source.refresh_value().unwrap();
+
+        assert_eq!(source.value().unwrap(), 8080);
+        let path = source.path().display().to_string();
+        assert_eq!(
+            *observer.0.lock().unwrap(),
+            [(path.clone(), true), (path, false)]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    fs::File,
+    io,
+    path::Path,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    FileSource, FsBackend, RefreshFileSourceError, ValueOutcome,
+    backend::{ReadRecord, read_entry},
+};
+
+/// Opens the files an [`FsBackend`] reads, replaceable for instrumentation or tests with
+/// [`FsBackend::set_opener`] and [`FileSource::set_opener`].
+pub trait Opener: Send + Sync {
+    fn open(&self, path: &Path) -> io::Result<File>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FsOpener;
+
+impl Opener for FsOpener {
+    fn open(&self, path: &Path) -> io::Result<File> {
+        File::open(path)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Reads files opened by `opener`, replacing the backend with an [`FsBackend`] using it.
+    pub fn set_opener(&mut self, opener: Arc<dyn Opener>) -> &mut Self {
+        let mut backend = FsBackend::new();
+        backend.set_opener(opener);
+        self.set_backend(Some(Arc::new(backend)))
+    }
+
+    /// Fail refreshes with [`RefreshFileSourceError::Timeout`] if fetching the entry from the
+    /// backend takes longer than `timeout`, keeping the previous value, e.g. on a hanging
+    /// network mount.
+    ///
+    /// The entry is read on a helper thread, which is left behind if the read never returns.
+    /// While it hangs, refreshes fail with [`RefreshFileSourceError::Timeout`] right away
+    /// instead of piling up more threads. The read buffer is not reused and memory mapping is
+    /// not used while a timeout is set.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    pub(crate) fn read_value_with_timeout(
+        &self,
+        timeout: Duration,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        if self.read_in_flight.swap(true, Ordering::AcqRel) {
+            return Err(RefreshFileSourceError::Timeout);
+        }
+        let in_flight = InFlight(self.read_in_flight.clone());
+        let (sender, receiver) = mpsc::channel();
+        let backend = self.backend.clone();
+        let location = self.location();
+        thread::spawn(move || {
+            let read = read_entry(&*backend, &location);
+            drop(in_flight);
+            let _ = sender.send(read);
+        });
+
+        let read = match receiver.recv_timeout(timeout) {
+            Ok(read) => read?,
+            Err(RecvTimeoutError::Timeout) => return Err(RefreshFileSourceError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(io::Error::other("read thread panicked").into());
+            }
+        };
+        self.parse_raw(read.as_ref(), record)
+    }
+}
+
+/// Clears the in-flight flag of a timed read once its helper thread is done, even if it panics.
+struct InFlight(Arc<AtomicBool>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, time::Instant};
+
+    use super::*;
+    use crate::ValueSource;
+
+    #[derive(Default)]
+    struct SlowOpener(AtomicUsize);
+
+    impl Opener for SlowOpener {
+        fn open(&self, path: &Path) -> io::Result<File> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_secs(2));
+            File::open(path)
+        }
+    }
+
+    #[test]
+    fn read_timeout_keeps_cache() {
+        let mut source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        source.set_read_timeout(Some(Duration::from_millis(500)));
+        assert_eq!(source.value().unwrap(), "hello world!");
+
+        let opener = Arc::new(SlowOpener::default());
+        source
+            .set_opener(opener.clone())
+            .set_read_timeout(Some(Duration::from_millis(50)));
+        let start = Instant::now();
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::Timeout)
+        ));
+        assert!(matches!(
+            source.refresh_value(),
+            Err(RefreshFileSourceError::Timeout)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(opener.0.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            source.cached(),
+            ValueOutcome::Loaded("hello world!".to_owned())
+        );
+    }
+}
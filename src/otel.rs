@@ -0,0 +1,94 @@
+use opentelemetry::{
+    KeyValue,
+    metrics::{Counter, Histogram, Meter},
+};
+
+use crate::{RefreshEvent, RefreshObserver};
+
+/// [`RefreshObserver`] recording `k8s_config.refresh.count`, `k8s_config.refresh.errors` and
+/// the `k8s_config.refresh.duration` histogram in seconds, with the file path as `path`
+/// attribute.
+#[derive(Debug, Clone)]
+pub struct OtelObserver {
+    count: Counter<u64>,
+    errors: Counter<u64>,
+    duration: Histogram<f64>,
+}
+
+impl OtelObserver {
+    pub fn new(meter: &Meter) -> Self {
+        Self {
+            count: meter
+                .u64_counter("k8s_config.refresh.count")
+                .with_description("Number of config file refreshes")
+                .build(),
+            errors: meter
+                .u64_counter("k8s_config.refresh.errors")
+                .with_description("Number of failed config file refreshes")
+                .build(),
+            duration: meter
+                .f64_histogram("k8s_config.refresh.duration")
+                .with_description("Duration of config file refreshes")
+                .with_unit("s")
+                .build(),
+        }
+    }
+}
+
+impl RefreshObserver for OtelObserver {
+    fn on_refresh(&self, event: &RefreshEvent<'_>) {
+        let attributes = [KeyValue::new("path", event.path.display().to_string())];
+        self.count.add(1, &attributes);
+        if event.error.is_some() {
+            self.errors.add(1, &attributes);
+        }
+        self.duration
+            .record(event.duration.as_secs_f64(), &attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::{
+        InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+        data::{AggregatedMetrics, MetricData},
+    };
+
+    use super::*;
+    use crate::{FileSource, ValueSource};
+
+    #[test]
+    fn counts_refreshes() {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let observer = OtelObserver::new(&provider.meter("k8s-config"));
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_observer(Some(Arc::new(observer)));
+
+        source.value().unwrap();
+        provider.force_flush().unwrap();
+
+        let metrics = exporter.get_finished_metrics().unwrap();
+        let count = metrics
+            .iter()
+            .flat_map(|resource| resource.scope_metrics())
+            .flat_map(|scope| scope.metrics())
+            .find(|metric| metric.name() == "k8s_config.refresh.count")
+            .unwrap();
+        let AggregatedMetrics::U64(MetricData::Sum(sum)) = count.data() else {
+            panic!("unexpected count data {:?}", count.data());
+        };
+        let point = sum.data_points().next().unwrap();
+        assert_eq!(point.value(), 1);
+        assert_eq!(
+            point.attributes().next(),
+            Some(&KeyValue::new("path", "sources/test-port"))
+        );
+        provider.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    convert::Infallible,
+    path::{Path, PathBuf},
+};
+
+use crate::{FileSource, RefreshFileSourceError, ValidationError, ValueError, ValueSource};
+
+/// Source whose content is itself a path (e.g. `TLS_CERT_PATH`), optionally verifying that
+/// the referenced file exists whenever the value is read.
+pub struct PathFileSource<const REQUIRED: bool> {
+    source: FileSource<PathBuf, REQUIRED>,
+    require_exists: bool,
+}
+
+impl<const REQUIRED: bool> PathFileSource<REQUIRED> {
+    pub fn new(source: FileSource<PathBuf, REQUIRED>) -> Self {
+        Self {
+            source,
+            require_exists: false,
+        }
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<PathBuf, REQUIRED> {
+        &mut self.source
+    }
+
+    /// Fail reading the value with a [`ValidationError`] unless the referenced path is an
+    /// existing file. Checked in addition to any validator set on the wrapped source.
+    pub fn require_exists(&mut self, require_exists: bool) -> &mut Self {
+        self.require_exists = require_exists;
+        self
+    }
+
+    fn check_exists(&self, path: &Path) -> Result<(), ValueError<Infallible>> {
+        if !self.require_exists {
+            return Ok(());
+        }
+
+        let error = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => return Ok(()),
+            Ok(_) => format!("referenced path {} is not a file", path.display()),
+            Err(e) => format!("referenced path {} does not exist: {e}", path.display()),
+        };
+        Err(RefreshFileSourceError::ValidationError(ValidationError(vec![error])).into())
+    }
+}
+
+impl ValueSource<PathBuf, Infallible> for PathFileSource<true> {
+    fn value(&self) -> Result<PathBuf, ValueError<Infallible>> {
+        let path = self.source.value()?;
+        self.check_exists(&path)?;
+        Ok(path)
+    }
+}
+
+impl ValueSource<Option<PathBuf>, Infallible> for PathFileSource<false> {
+    fn value(&self) -> Result<Option<PathBuf>, ValueError<Infallible>> {
+        let path = self.source.value()?;
+        if let Some(path) = &path {
+            self.check_exists(path)?;
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Validator;
+
+    #[test]
+    fn existing_target() {
+        let mut source =
+            PathFileSource::<true>::new(FileSource::from_path("sources/test-path-existing".into()));
+        source.require_exists(true);
+
+        assert_eq!(
+            source.value().unwrap(),
+            PathBuf::from("sources/test-required")
+        );
+    }
+
+    #[test]
+    fn missing_target() {
+        let unchecked =
+            PathFileSource::<true>::new(FileSource::from_path("sources/test-path-dangling".into()));
+        assert_eq!(
+            unchecked.value().unwrap(),
+            PathBuf::from("sources/test-path-target-missing")
+        );
+
+        let mut source =
+            PathFileSource::<true>::new(FileSource::from_path("sources/test-path-dangling".into()));
+        source.require_exists(true);
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ValidationError(ValidationError(errors))
+            )) if errors[0].contains("sources/test-path-target-missing")
+        ));
+    }
+
+    #[test]
+    fn directory_target() {
+        let mut source =
+            PathFileSource::<false>::new(FileSource::from_path("sources/test-path-dir".into()));
+        source.require_exists(true);
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ValidationError(ValidationError(errors))
+            )) if errors[0].contains("is not a file")
+        ));
+    }
+
+    #[test]
+    fn keeps_source_validator() {
+        let mut source =
+            PathFileSource::<true>::new(FileSource::from_path("sources/test-path-existing".into()));
+        source
+            .source_mut()
+            .set_validator(Validator::new().custom(|path: &PathBuf| {
+                if path.is_absolute() {
+                    Ok(())
+                } else {
+                    Err("path must be absolute".to_owned())
+                }
+            }));
+        source.require_exists(true).require_exists(false);
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ValidationError(ValidationError(errors))
+            )) if errors == ["path must be absolute"]
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fs::Metadata, os::unix::fs::PermissionsExt, path::Path, str::FromStr};
+
+use crate::FileSource;
+
+/// Permission bits that make a file readable by group or others.
+const SHARED_READ_MODE: u32 = 0o044;
+
+/// Called with the path and mode of a file that is readable by group or others.
+pub type PermissionCallback = Box<dyn Fn(&Path, u32) + Send + Sync>;
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Check on every refresh whether the file is group or world readable, which usually
+    /// means a Secret volume's `defaultMode` is too permissive, reporting such files to the
+    /// callback set via [`FileSource::set_on_insecure_permissions`].
+    pub fn set_check_permissions(&mut self, check_permissions: bool) -> &mut Self {
+        self.check_permissions = check_permissions;
+        self
+    }
+
+    pub fn set_on_insecure_permissions(&mut self, callback: PermissionCallback) -> &mut Self {
+        self.on_insecure_permissions = Some(callback);
+        self
+    }
+
+    pub(crate) fn check_permissions(&self, metadata: &Metadata) {
+        let (true, Some(callback)) = (self.check_permissions, &self.on_insecure_permissions) else {
+            return;
+        };
+
+        let mode = metadata.permissions().mode();
+        if mode & SHARED_READ_MODE != 0 {
+            callback(&self.location().path(), mode);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    };
+
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    fn warns_for_mode(mode: u32) -> bool {
+        let mut source: TestSource<String, true> = TestSource::new("secret");
+        fs::set_permissions(source.path(), fs::Permissions::from_mode(mode)).unwrap();
+        let warned = Arc::new(AtomicBool::new(false));
+        let callback_warned = warned.clone();
+        source
+            .set_check_permissions(true)
+            .set_on_insecure_permissions(Box::new(move |_, _| {
+                callback_warned.store(true, Ordering::SeqCst)
+            }));
+
+        assert_eq!(source.value().unwrap(), "secret");
+        warned.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn world_readable_warns() {
+        assert!(warns_for_mode(0o644));
+    }
+
+    #[test]
+    fn owner_only_does_not_warn() {
+        assert!(!warns_for_mode(0o400));
+    }
+}
@@ -0,0 +1,54 @@
+use std::{str::FromStr, time::SystemTime};
+
+use crate::FileSource;
+
+/// Returns `true` for the wall-clock times the file may be re-read at.
+pub type RefreshWindow = Box<dyn Fn(SystemTime) -> bool + Send + Sync>;
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Only re-read the file while `window` allows it, e.g. during maintenance windows, and
+    /// keep serving the last value otherwise. The first read always happens.
+    pub fn set_refresh_window(&mut self, window: RefreshWindow) -> &mut Self {
+        self.refresh_window = Some(window);
+        self
+    }
+
+    pub(crate) fn outside_refresh_window(&self) -> bool {
+        self.refresh_window.as_ref().is_some_and(|window| {
+            self.last_refresh().is_some() && !window(self.clock.system_time())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Clock, TestSource, ValueSource};
+
+    #[test]
+    fn refreshes_only_inside_window() {
+        let mut source: TestSource<String, true> = TestSource::new("first");
+        let opens_at = source.clock().system_time() + Duration::from_secs(60);
+        let closes_at = opens_at + Duration::from_secs(60);
+        source
+            .set_refresh_interval(Some(Duration::from_secs(1)))
+            .set_refresh_window(Box::new(move |now| opens_at <= now && now < closes_at));
+        assert_eq!(source.value().unwrap(), "first");
+
+        source.write("second");
+        source.advance(Duration::from_secs(30));
+        assert_eq!(source.value().unwrap(), "first");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), "first");
+
+        source.advance(Duration::from_secs(40));
+        assert_eq!(source.value().unwrap(), "second");
+
+        source.write("third");
+        source.advance(Duration::from_secs(60));
+        assert_eq!(source.value().unwrap(), "second");
+    }
+}
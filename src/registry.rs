@@ -0,0 +1,354 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{DurationValue, FileSource, Refreshable, ValueError, ValueOutcome};
+
+/// Value of a type-erased source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigValue {
+    String(String),
+    Int(i64),
+    Bool(bool),
+    Duration(Duration),
+}
+
+impl From<String> for ConfigValue {
+    fn from(value: String) -> Self {
+        ConfigValue::String(value)
+    }
+}
+
+impl From<i64> for ConfigValue {
+    fn from(value: i64) -> Self {
+        ConfigValue::Int(value)
+    }
+}
+
+impl From<bool> for ConfigValue {
+    fn from(value: bool) -> Self {
+        ConfigValue::Bool(value)
+    }
+}
+
+impl From<DurationValue> for ConfigValue {
+    fn from(value: DurationValue) -> Self {
+        ConfigValue::Duration(value.0)
+    }
+}
+
+/// Source with its value type erased, so differently typed sources can be kept together.
+pub trait ErasedSource: Refreshable {
+    /// Refreshes on timeout and returns the current value, `None` if an optional source has
+    /// none.
+    fn erased_value(&self) -> Result<Option<ConfigValue>, Box<dyn Error + Send + Sync>>;
+
+    /// Reads and checks the source without changing it, see [`FileSource::validate`]. Sources
+    /// that can't check their file without refreshing accept by default.
+    fn validate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Estimated memory held by the cached value, `None` if nothing is cached or the source
+    /// doesn't track it, which is the default.
+    fn cache_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Drops the cached value and returns whether it did, see [`FileSource::evict`]. Sources
+    /// that can't restore their value keep it by default.
+    fn evict(&self) -> bool {
+        false
+    }
+}
+
+impl<E, T, const REQUIRED: bool, I> ErasedSource for FileSource<T, REQUIRED, I>
+where
+    E: Error + Send + Sync + 'static,
+    T: FromStr<Err = E> + Clone + Into<ConfigValue> + Send + Sync,
+{
+    fn erased_value(&self) -> Result<Option<ConfigValue>, Box<dyn Error + Send + Sync>> {
+        self.refresh_on_timeout()?;
+        match self.cached() {
+            ValueOutcome::Loaded(value) => Ok(Some(value.into())),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing if REQUIRED => {
+                Err(ValueError::<E>::NoValue.into())
+            }
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => Ok(None),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(FileSource::validate(self)?)
+    }
+
+    /// The size of the file contents the value was parsed from.
+    fn cache_size(&self) -> Option<usize> {
+        match self.cached() {
+            ValueOutcome::Loaded(_) => self.last_read_bytes(),
+            ValueOutcome::FileAbsent | ValueOutcome::EmptyTreatedMissing => None,
+        }
+    }
+
+    fn evict(&self) -> bool {
+        FileSource::evict(self)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RegistryError {
+    #[error("no source registered as {0:?}")]
+    UnknownSource(String),
+    #[error("error reading source {0:?}: {1}")]
+    Source(String, Box<dyn Error + Send + Sync>),
+}
+
+/// Named collection of type-erased sources.
+///
+/// Clones share the access order used to enforce the memory budget.
+#[derive(Default, Clone)]
+pub struct SourceRegistry {
+    sources: HashMap<String, Arc<dyn ErasedSource>>,
+    memory_budget: Option<usize>,
+    accesses: Arc<Mutex<Accesses>>,
+}
+
+/// Order of accesses through [`SourceRegistry::value`], for least recently used eviction.
+#[derive(Default)]
+struct Accesses {
+    counter: u64,
+    last: HashMap<String, u64>,
+}
+
+impl std::fmt::Debug for SourceRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SourceRegistry")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, source: Arc<dyn ErasedSource>) -> &mut Self {
+        self.sources.insert(name.into(), source);
+        self
+    }
+
+    /// Bound the estimated memory of all cached values to `bytes`. When an access through
+    /// [`SourceRegistry::value`] exceeds the budget, the least recently accessed sources are
+    /// evicted until it fits again. The accessed source itself is never evicted.
+    pub fn set_memory_budget(&mut self, bytes: Option<usize>) -> &mut Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn ErasedSource>> {
+        self.sources.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.keys().map(String::as_str)
+    }
+
+    pub fn value(&self, name: &str) -> Result<Option<ConfigValue>, RegistryError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownSource(name.to_owned()))?
+            .erased_value()
+            .map_err(|e| RegistryError::Source(name.to_owned(), e));
+        if let Some(budget) = self.memory_budget {
+            self.enforce_memory_budget(name, budget);
+        }
+        value
+    }
+
+    /// Records the access to `name`, then evicts the least recently accessed other sources until
+    /// the cached values fit into `budget`.
+    fn enforce_memory_budget(&self, name: &str, budget: usize) {
+        let mut accesses = self
+            .accesses
+            .lock()
+            .expect("error locking accesses in SourceRegistry");
+        accesses.counter += 1;
+        let counter = accesses.counter;
+        accesses.last.insert(name.to_owned(), counter);
+
+        let mut cached: Vec<_> = self
+            .sources
+            .iter()
+            .filter_map(|(source_name, source)| {
+                let last_access = accesses.last.get(source_name).copied().unwrap_or_default();
+                source
+                    .cache_size()
+                    .map(|size| (source_name == name, last_access, size, source))
+            })
+            .collect();
+        let mut total: usize = cached.iter().map(|(_, _, size, _)| size).sum();
+        cached.sort_unstable_by_key(|(_, last_access, _, _)| *last_access);
+        for (accessed, _, size, source) in cached {
+            if total <= budget {
+                break;
+            }
+            if !accessed && source.evict() {
+                total -= size;
+            }
+        }
+    }
+
+    /// Validates every source without touching their cached values, reporting all failures,
+    /// sorted by name.
+    pub fn validate(&self) -> Result<(), Vec<RegistryError>> {
+        let mut errors: Vec<_> = self
+            .sources
+            .iter()
+            .filter_map(|(name, source)| {
+                source
+                    .validate()
+                    .err()
+                    .map(|e| (name, RegistryError::Source(name.clone(), e)))
+            })
+            .collect();
+        errors.sort_by_key(|(name, _)| *name);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into_iter().map(|(_, e)| e).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erased_values() {
+        let mut registry = SourceRegistry::new();
+        registry
+            .insert(
+                "greeting",
+                Arc::new(FileSource::<String, true>::from_path(
+                    "sources/test-required".into(),
+                )),
+            )
+            .insert(
+                "port",
+                Arc::new(FileSource::<i64, false>::from_path(
+                    "sources/test-port".into(),
+                )),
+            );
+
+        assert_eq!(
+            registry.value("greeting").unwrap(),
+            Some(ConfigValue::String("hello world!".to_owned()))
+        );
+        assert_eq!(
+            registry.value("port").unwrap(),
+            Some(ConfigValue::Int(8080))
+        );
+        assert!(matches!(
+            registry.value("missing"),
+            Err(RegistryError::UnknownSource(_))
+        ));
+    }
+
+    #[test]
+    fn memory_budget_evicts_lru() {
+        let sources = [
+            "sources/test-required",
+            "sources/test-port",
+            "sources/test-list",
+        ]
+        .map(|path| Arc::new(FileSource::<String, true>::from_path(path.into())));
+        let mut registry = SourceRegistry::new();
+        registry
+            .insert("greeting", sources[0].clone())
+            .insert("port", sources[1].clone())
+            .insert("list", sources[2].clone())
+            .set_memory_budget(Some(24));
+
+        for name in ["greeting", "port", "list"] {
+            registry.value(name).unwrap();
+        }
+        assert_eq!(sources[0].cache_size(), None);
+        assert!(sources[1].cache_size().is_some());
+        assert!(sources[2].cache_size().is_some());
+
+        assert_eq!(
+            registry.value("greeting").unwrap(),
+            Some(ConfigValue::String("hello world!".to_owned()))
+        );
+        assert_eq!(sources[1].cache_size(), None);
+    }
+
+    #[test]
+    fn validate_all() {
+        let mut registry = SourceRegistry::new();
+        registry
+            .insert(
+                "port",
+                Arc::new(FileSource::<i64, true>::from_path(
+                    "sources/test-port".into(),
+                )),
+            )
+            .insert(
+                "greeting",
+                Arc::new(FileSource::<i64, true>::from_path(
+                    "sources/test-required".into(),
+                )),
+            )
+            .insert(
+                "absent",
+                Arc::new(FileSource::<String, true>::from_path(
+                    "sources/test-registry-missing".into(),
+                )),
+            );
+
+        let names: Vec<String> = registry
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| match e {
+                RegistryError::Source(name, _) => name,
+                RegistryError::UnknownSource(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(names, ["absent", "greeting"]);
+    }
+
+    #[test]
+    fn memory_budget_keeps_pinned_and_accessed_sources() {
+        let sources = [
+            "sources/test-required",
+            "sources/test-port",
+            "sources/test-list",
+        ]
+        .map(|path| Arc::new(FileSource::<String, true>::from_path(path.into())));
+        let mut registry = SourceRegistry::new();
+        registry
+            .insert("greeting", sources[0].clone())
+            .insert("port", sources[1].clone())
+            .insert("list", sources[2].clone())
+            .set_memory_budget(Some(1));
+
+        registry.value("greeting").unwrap();
+        sources[0].freeze();
+        registry.value("port").unwrap();
+        assert!(sources[0].cache_size().is_some());
+        assert!(sources[1].cache_size().is_some());
+
+        registry.value("list").unwrap();
+        assert!(sources[0].cache_size().is_some());
+        assert_eq!(sources[1].cache_size(), None);
+        assert!(sources[2].cache_size().is_some());
+    }
+}
@@ -0,0 +1,76 @@
+use std::{str::FromStr, sync::atomic::Ordering};
+
+use crate::FileSource;
+
+pub type RemovedCallback = Box<dyn Fn() + Send + Sync>;
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Calls `on_removed` when a refresh finds the file gone after the previous one read it.
+    /// A file that never existed doesn't trigger it, and neither do reads that don't refresh
+    /// the source, such as [`FileSource::validate`].
+    pub fn set_on_removed(&mut self, on_removed: RemovedCallback) -> &mut Self {
+        self.on_removed = Some(on_removed);
+        self
+    }
+
+    pub(crate) fn record_present(&self) {
+        self.present.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_absent(&self) {
+        if self.present.swap(false, Ordering::Relaxed)
+            && let Some(on_removed) = &self.on_removed
+        {
+            on_removed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, atomic::AtomicUsize};
+
+    use super::*;
+    use crate::TestSource;
+
+    #[test]
+    fn fires_once_on_removal() {
+        let removals = Arc::new(AtomicUsize::new(0));
+        let mut source: TestSource<String, false> = TestSource::missing();
+        let counter = removals.clone();
+        source.set_on_removed(Box::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        source.refresh_value().unwrap();
+        assert_eq!(removals.load(Ordering::Relaxed), 0);
+
+        source.write("present");
+        source.refresh_value().unwrap();
+        source.remove();
+        source.refresh_value().unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(removals.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn dry_runs_do_not_fire() {
+        let removals = Arc::new(AtomicUsize::new(0));
+        let mut source: TestSource<String, false> = TestSource::new("present");
+        let counter = removals.clone();
+        source.set_on_removed(Box::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+        source.refresh_value().unwrap();
+
+        source.remove();
+        source.validate().unwrap();
+        assert_eq!(source.with_contents(str::len).unwrap(), None);
+        assert_eq!(removals.load(Ordering::Relaxed), 0);
+
+        source.refresh_value().unwrap();
+        assert_eq!(removals.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,68 @@
+use std::{convert::Infallible, num::ParseIntError, path::Path, str::ParseBoolError};
+
+use crate::{RefreshFileSourceError, read_once};
+
+fn read_required<T: std::str::FromStr>(
+    path: impl AsRef<Path>,
+) -> Result<T, RefreshFileSourceError<T::Err>>
+where
+    T::Err: std::fmt::Debug,
+{
+    read_once(path, true, true).map(|value| value.expect("required read always returns a value"))
+}
+
+/// Reads a required file holding a single value, without caching; see [`read_once`].
+pub fn read_string(path: impl AsRef<Path>) -> Result<String, RefreshFileSourceError<Infallible>> {
+    read_required(path)
+}
+
+/// Parses `true` or `false`.
+pub fn read_bool(path: impl AsRef<Path>) -> Result<bool, RefreshFileSourceError<ParseBoolError>> {
+    read_required(path)
+}
+
+pub fn read_u16(path: impl AsRef<Path>) -> Result<u16, RefreshFileSourceError<ParseIntError>> {
+    read_required(path)
+}
+
+pub fn read_u32(path: impl AsRef<Path>) -> Result<u32, RefreshFileSourceError<ParseIntError>> {
+    read_required(path)
+}
+
+pub fn read_u64(path: impl AsRef<Path>) -> Result<u64, RefreshFileSourceError<ParseIntError>> {
+    read_required(path)
+}
+
+pub fn read_i64(path: impl AsRef<Path>) -> Result<i64, RefreshFileSourceError<ParseIntError>> {
+    read_required(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_scalars() {
+        assert_eq!(
+            read_string("sources/test-required").unwrap(),
+            "hello world!"
+        );
+        assert!(read_bool("sources/test-bool").unwrap());
+        assert_eq!(read_u16("sources/test-port").unwrap(), 8080);
+        assert_eq!(read_u32("sources/test-port").unwrap(), 8080);
+        assert_eq!(read_u64("sources/test-port").unwrap(), 8080);
+        assert_eq!(read_i64("sources/test-port").unwrap(), 8080);
+    }
+
+    #[test]
+    fn read_scalar_errors() {
+        assert!(matches!(
+            read_bool("sources/test-port"),
+            Err(RefreshFileSourceError::ParseError(_))
+        ));
+        assert!(matches!(
+            read_u16("sources/test-scalar-missing"),
+            Err(RefreshFileSourceError::NoValue)
+        ));
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+use jsonschema::Validator;
+use serde_json::Value;
+
+/// Violations of a JSON Schema, one message per failing keyword prefixed with the location in
+/// the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError(pub Vec<String>);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join("; "))
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Compiles `schema`, reporting an invalid schema as a single violation.
+pub(crate) fn compile(schema: &Value) -> Result<Validator, SchemaError> {
+    jsonschema::validator_for(schema).map_err(|e| SchemaError(vec![format!("invalid schema: {e}")]))
+}
+
+pub(crate) fn check(validator: &Validator, document: &Value) -> Result<(), SchemaError> {
+    let violations: Vec<String> = validator
+        .iter_errors(document)
+        .map(|e| match e.instance_path().as_str() {
+            "" => e.to_string(),
+            path => format!("{path}: {e}"),
+        })
+        .collect();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(SchemaError(violations))
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    io,
+    str::FromStr,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Clock, FileSource, Location, RefreshFileSourceError, SecretBackend, SystemClock, ValueOutcome,
+    backend::{RawRead, ReadRecord, read_entry},
+};
+
+type SharedRead = Result<Option<Arc<RawRead>>, Arc<io::Error>>;
+
+struct Entry {
+    read_at: Instant,
+    /// Keeps the backend alive, so its address in the entry's key isn't reused.
+    _backend: Arc<dyn SecretBackend>,
+    read: OnceLock<SharedRead>,
+}
+
+/// Read cache shared by sources that may point at the same file, attached with
+/// [`FileSource::set_shared_read_cache`]. Reads of the same entry through the same backend
+/// within `window` of each other are served from a single read, with entries told apart by
+/// [`SecretBackend::entry_key`].
+///
+/// Sources asking for a file that is being read wait for that read instead of starting their
+/// own; reads of different files don't block each other. Each source still decodes, parses and
+/// checks the contents with its own settings. Memory mapping and read timeouts are not used
+/// for sources sharing the cache.
+pub struct SharedReadCache {
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    entries: Mutex<HashMap<(usize, Location), Arc<Entry>>>,
+}
+
+impl SharedReadCache {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            clock: Arc::new(SystemClock),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    pub(crate) fn read(
+        &self,
+        backend: &Arc<dyn SecretBackend>,
+        location: &Location,
+    ) -> io::Result<Option<Arc<RawRead>>> {
+        let entry_key = match location {
+            Location::Path(path) => Location::Path(backend.entry_key(path)),
+            #[cfg(unix)]
+            Location::Fd(_) => location.clone(),
+        };
+        let key = (Arc::as_ptr(backend).cast::<()>() as usize, entry_key);
+        let now = self.clock.now();
+        let entry = {
+            let mut entries = self
+                .entries
+                .lock()
+                .expect("error locking entries in SharedReadCache");
+            match entries.get(&key) {
+                Some(entry) if now.duration_since(entry.read_at) < self.window => entry.clone(),
+                _ => {
+                    entries.retain(|_, entry| now.duration_since(entry.read_at) < self.window);
+                    let entry = Arc::new(Entry {
+                        read_at: now,
+                        _backend: backend.clone(),
+                        read: OnceLock::new(),
+                    });
+                    entries.insert(key.clone(), entry.clone());
+                    entry
+                }
+            }
+        };
+
+        let read = entry.read.get_or_init(|| {
+            read_entry(&**backend, location)
+                .map(|read| read.map(Arc::new))
+                .map_err(Arc::new)
+        });
+        read.clone().map_err(|e| match e.raw_os_error() {
+            Some(code) => io::Error::from_raw_os_error(code),
+            // The original error stays available as the inner error.
+            None => io::Error::new(e.kind(), e),
+        })
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    pub fn set_shared_read_cache(&mut self, cache: Option<Arc<SharedReadCache>>) -> &mut Self {
+        self.shared_read_cache = cache;
+        self
+    }
+
+    pub(crate) fn read_value_shared(
+        &self,
+        cache: &SharedReadCache,
+        record: &mut ReadRecord,
+    ) -> Result<ValueOutcome<T>, RefreshFileSourceError<E>> {
+        let read = cache.read(&self.backend, &self.location())?;
+        self.parse_raw(read.as_deref(), record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        path::Path,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+    use crate::{FsBackend, MockClock, Opener, ValueSource};
+
+    #[derive(Default)]
+    struct CountingOpener(AtomicUsize);
+
+    impl Opener for CountingOpener {
+        fn open(&self, path: &Path) -> io::Result<File> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            File::open(path)
+        }
+    }
+
+    #[test]
+    fn single_read_per_window() {
+        let opener = Arc::new(CountingOpener::default());
+        let mut backend = FsBackend::new();
+        backend.set_opener(opener.clone());
+        let backend: Arc<dyn SecretBackend> = Arc::new(backend);
+        let clock = MockClock::new();
+        let mut cache = SharedReadCache::new(Duration::from_secs(1));
+        cache.set_clock(Arc::new(clock.clone()));
+        let cache = Arc::new(cache);
+
+        let mut greeting: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        greeting
+            .set_backend(Some(backend.clone()))
+            .set_shared_read_cache(Some(cache.clone()));
+        let mut same_file: FileSource<String, true> =
+            FileSource::from_path("sources/../sources/test-required".into());
+        same_file
+            .set_backend(Some(backend.clone()))
+            .set_shared_read_cache(Some(cache.clone()))
+            .set_auto_trim(false);
+
+        assert_eq!(greeting.value().unwrap(), "hello world!");
+        assert_eq!(same_file.value().unwrap(), "hello world!\n");
+        assert_eq!(opener.0.load(Ordering::Relaxed), 1);
+
+        let mut port: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        port.set_backend(Some(backend))
+            .set_shared_read_cache(Some(cache.clone()));
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(port.value().unwrap(), 8080);
+        same_file.refresh_value().unwrap();
+        assert_eq!(opener.0.load(Ordering::Relaxed), 3);
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rooted_backend_keys_not_resolved_against_cwd() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("sources")).unwrap();
+        std::fs::write(root.path().join("sources/test-port"), "9090").unwrap();
+        let cache = Arc::new(SharedReadCache::new(Duration::from_secs(1)));
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source
+            .set_backend(Some(Arc::new(FsBackend::with_root(root.path().to_owned()))))
+            .set_shared_read_cache(Some(cache));
+
+        assert_eq!(source.value().unwrap(), 9090);
+    }
+
+    #[test]
+    fn forwards_read_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(SharedReadCache::new(Duration::from_secs(1)));
+        let mut source: FileSource<String, true> = FileSource::from_path(dir.path().to_owned());
+        source.set_shared_read_cache(Some(cache));
+
+        let Err(RefreshFileSourceError::IOError(e)) = source.refresh_value() else {
+            panic!("expected a read error");
+        };
+        assert_eq!(
+            e.raw_os_error(),
+            std::fs::read(dir.path()).unwrap_err().raw_os_error()
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    ffi::c_int,
+    io,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+use signal_hook::iterator::{Handle, Signals};
+
+use crate::Refreshable;
+
+/// Signal number, e.g. [`signal_hook::consts::SIGHUP`].
+pub type Signal = c_int;
+
+/// Refreshes all `sources` whenever the process receives `signal`, the classic
+/// `kill -HUP` reload, until the returned [`SignalReloader`] is dropped.
+///
+/// The handler replaces the signal's default action, so SIGHUP no longer terminates the
+/// process. Refresh errors stay visible through the sources themselves.
+pub fn reload_on_signal(
+    sources: Vec<Arc<dyn Refreshable>>,
+    signal: Signal,
+) -> io::Result<SignalReloader> {
+    let mut signals = Signals::new([signal])?;
+    let handle = signals.handle();
+    let thread = thread::spawn(move || {
+        for _ in signals.forever() {
+            for source in &sources {
+                let _ = source.refresh();
+            }
+        }
+    });
+
+    Ok(SignalReloader {
+        handle,
+        thread: Some(thread),
+    })
+}
+
+/// Guard of [`reload_on_signal`], unregistering the handler when dropped.
+pub struct SignalReloader {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for SignalReloader {
+    fn drop(&mut self) {
+        self.handle.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use signal_hook::consts::SIGUSR1;
+
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    #[test]
+    fn refreshes_on_signal() {
+        let source: Arc<TestSource<u16, true>> = Arc::new(TestSource::new("8080"));
+        assert_eq!(source.value().unwrap(), 8080);
+        let _reloader = reload_on_signal(vec![source.clone()], SIGUSR1).unwrap();
+
+        source.write("9090");
+        assert_eq!(source.value().unwrap(), 8080);
+        signal_hook::low_level::raise(SIGUSR1).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while source.value().unwrap() != 9090 {
+            assert!(Instant::now() < deadline, "source not refreshed on signal");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
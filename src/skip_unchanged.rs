@@ -0,0 +1,124 @@
+use std::{fs::Metadata, str::FromStr, time::SystemTime};
+
+use crate::{FileSource, ValueOutcome};
+
+/// Size and modified time of the file a source last read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReadMark {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl From<&Metadata> for ReadMark {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+impl ReadMark {
+    /// Whether the file is definitely modified since `self` was taken. The size is compared
+    /// first as the cheapest gate, but an equal size alone doesn't mean unchanged.
+    pub(crate) fn changed(&self, metadata: &Metadata) -> bool {
+        self.len != metadata.len()
+            || self
+                .modified
+                .is_none_or(|m| metadata.modified().ok() != Some(m))
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Skip reading and parsing the file when its size and modified time are unchanged since
+    /// the last read, keeping the cached value. Edits that keep both, e.g. within the mtime
+    /// resolution, go unnoticed until the file changes again.
+    pub fn set_skip_unchanged(&mut self, skip_unchanged: bool) -> &mut Self {
+        self.skip_unchanged = skip_unchanged;
+        self
+    }
+
+    /// Cached value if skipping unchanged files and the file is unchanged since the last
+    /// committed read.
+    pub(crate) fn cached_if_unchanged(&self, metadata: &Metadata) -> Option<ValueOutcome<T>> {
+        if !self.skip_unchanged {
+            return None;
+        }
+        let last_read = *self
+            .last_read_mark
+            .read()
+            .expect("error reading last_read_mark in FileSource");
+        let cached = self.cached();
+        match (last_read, &cached) {
+            (Some(mark), ValueOutcome::Loaded(_)) if !mark.changed(metadata) => Some(cached),
+            _ => None,
+        }
+    }
+
+    /// Remembers the mark of the file a successful refresh read, for the next refresh to
+    /// compare against.
+    pub(crate) fn store_read_mark(&self, mark: Option<ReadMark>) {
+        if self.skip_unchanged {
+            *self
+                .last_read_mark
+                .write()
+                .expect("error locking last_read_mark in FileSource") = mark;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, time::Duration};
+
+    use super::*;
+    use crate::{TestSource, ValueSource};
+
+    #[test]
+    fn unchanged_size_and_mtime_not_reread() {
+        let mut source: TestSource<u32, true> = TestSource::missing();
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let write = |source: &TestSource<u32, true>, contents: &str| {
+            source.write(contents);
+            File::options()
+                .write(true)
+                .open(source.path())
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        };
+        write(&source, "8080");
+        source.set_skip_unchanged(true);
+        assert_eq!(source.value().unwrap(), 8080);
+
+        write(&source, "9090");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), 8080);
+
+        write(&source, "90901");
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), 90901);
+
+        source.write("not a port");
+        source.refresh_value().unwrap_err();
+        source.refresh_value().unwrap_err();
+    }
+
+    #[test]
+    fn mark_moves_on_successful_refresh() {
+        let mut source: TestSource<u32, true> = TestSource::new("8080");
+        source.set_skip_unchanged(true);
+        source.refresh_value().unwrap();
+
+        source.write("90901");
+        source.validate().unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(source.value().unwrap(), 90901);
+
+        source.write("not a port");
+        source.refresh_value().unwrap_err();
+        source.refresh_value().unwrap_err();
+    }
+}
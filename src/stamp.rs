@@ -0,0 +1,106 @@
+use std::{io, str::FromStr, time::SystemTime};
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome};
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Remembers the modified time of the file being read, stored along with its value.
+    pub(crate) fn record_modified(&self, modified: Option<SystemTime>) {
+        *self
+            .read_modified
+            .write()
+            .expect("error locking read_modified in FileSource") = modified;
+    }
+
+    /// Called by `set_value` with the value lock held, so value and stamp change together.
+    pub(crate) fn store_modified(&self, value: &ValueOutcome<T>) {
+        let read_modified = self
+            .read_modified
+            .write()
+            .expect("error locking read_modified in FileSource")
+            .take();
+        *self
+            .modified
+            .write()
+            .expect("error locking modified in FileSource") = match value {
+            ValueOutcome::Loaded(_) => read_modified,
+            _ => None,
+        };
+    }
+
+    /// Cached value together with the modified time of the file it was read from.
+    fn stamped(&self) -> Result<ValueOutcome<(T, SystemTime)>, RefreshFileSourceError<E>> {
+        let value = self
+            .value
+            .read()
+            .expect("error reading value in FileSource");
+        let modified = *self
+            .modified
+            .read()
+            .expect("error reading modified in FileSource");
+        Ok(match (&*value, modified) {
+            (ValueOutcome::Loaded(value), Some(modified)) => {
+                ValueOutcome::Loaded((value.clone(), modified))
+            }
+            (ValueOutcome::Loaded(_), None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "file modification time unavailable",
+                )
+                .into());
+            }
+            (ValueOutcome::FileAbsent, _) => ValueOutcome::FileAbsent,
+            (ValueOutcome::EmptyTreatedMissing, _) => ValueOutcome::EmptyTreatedMissing,
+        })
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, true, I> {
+    /// Refreshes on timeout and returns the value along with its file's modified time, read
+    /// under the same lock, e.g. to store `config@v<mtime>` and detect staleness later.
+    pub fn value_stamped(&self) -> Result<(T, SystemTime), ValueError<E>> {
+        self.refresh_on_timeout()?;
+        self.stamped()?.into_option().ok_or(ValueError::NoValue)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, I> FileSource<T, false, I> {
+    /// Refreshes on timeout and returns the value along with its file's modified time, read
+    /// under the same lock, `None` if the file is absent.
+    pub fn value_stamped(&self) -> Result<Option<(T, SystemTime)>, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        Ok(self.stamped()?.into_option())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, time::Duration};
+
+    use super::*;
+    use crate::TestSource;
+
+    #[test]
+    fn stamp_follows_mtime() {
+        let source: TestSource<String, true> = TestSource::new("first");
+        let file = File::options().write(true).open(source.path()).unwrap();
+        let first = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        file.set_modified(first).unwrap();
+
+        assert_eq!(source.value_stamped().unwrap(), ("first".to_owned(), first));
+
+        source.write("second");
+        let second = first + Duration::from_secs(60);
+        file.set_modified(second).unwrap();
+        source.refresh_value().unwrap();
+        assert_eq!(
+            source.value_stamped().unwrap(),
+            ("second".to_owned(), second)
+        );
+
+        let missing: FileSource<String, false> =
+            FileSource::from_path("sources/test-stamp-missing".into());
+        assert_eq!(missing.value_stamped().unwrap(), None);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::FileSource;
+
+/// Refresh counts of a source since it was created, see [`FileSource::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SourceStats {
+    /// Refreshes that read the file, `successes + errors`.
+    pub refreshes: u64,
+    pub successes: u64,
+    pub errors: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct RefreshCounters {
+    successes: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl RefreshCounters {
+    pub(crate) fn record(&self, success: bool) {
+        let counter = if success {
+            &self.successes
+        } else {
+            &self.errors
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Counts of all refreshes so far. Refreshes skipped because the source is frozen, waiting
+    /// for its ready marker or outside its refresh window are not counted; parse errors hidden
+    /// by [`FileSource::set_keep_last_good`] count as errors.
+    pub fn stats(&self) -> SourceStats {
+        let successes = self.refresh_counters.successes.load(Ordering::Relaxed);
+        let errors = self.refresh_counters.errors.load(Ordering::Relaxed);
+        SourceStats {
+            refreshes: successes + errors,
+            successes,
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestSource;
+
+    #[test]
+    fn counts_refreshes() {
+        let source: TestSource<u16, true> = TestSource::new("8080");
+        assert_eq!(source.stats(), SourceStats::default());
+
+        source.refresh_value().unwrap();
+        source.refresh_value().unwrap();
+        source.write("not a port");
+        source.refresh_value().unwrap_err();
+        source.write("9090");
+        source.refresh_value().unwrap();
+
+        assert_eq!(
+            source.stats(),
+            SourceStats {
+                refreshes: 4,
+                successes: 3,
+                errors: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::{FileSource, ValueOutcome};
+
+pub(crate) struct Subscribers<T> {
+    senders: Vec<UnboundedSender<T>>,
+    /// Recorded by [`FileSource::subscribe`], used unless a change comparator is set.
+    eq: Option<fn(&T, &T) -> bool>,
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            senders: Vec::new(),
+            eq: None,
+        }
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// The new value if storing `new` over `old` should notify subscribers.
+    pub(crate) fn subscriber_update(
+        &self,
+        old: &ValueOutcome<T>,
+        new: &ValueOutcome<T>,
+    ) -> Option<T> {
+        let subscribers = self
+            .subscribers
+            .lock()
+            .expect("error locking subscribers in FileSource");
+        let ValueOutcome::Loaded(new) = new else {
+            return None;
+        };
+        if subscribers.senders.is_empty() {
+            return None;
+        }
+
+        let changed = match (old, &self.change_comparator, subscribers.eq) {
+            (ValueOutcome::Loaded(old), Some(changed), _) => changed(old, new),
+            (ValueOutcome::Loaded(old), None, Some(eq)) => !eq(old, new),
+            (ValueOutcome::Loaded(_), None, None) => true,
+            _ => self.last_refresh().is_some(),
+        };
+        changed.then(|| new.clone())
+    }
+
+    /// Sends `value` to all subscribers, dropping those whose receiver is gone.
+    pub(crate) fn notify_subscribers(&self, value: T) {
+        self.subscribers
+            .lock()
+            .expect("error locking subscribers in FileSource")
+            .senders
+            .retain(|sender| sender.unbounded_send(value.clone()).is_ok());
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone + PartialEq, const REQUIRED: bool, I>
+    FileSource<T, REQUIRED, I>
+{
+    /// Receiver of every value a refresh loads that differs from the previous one, like
+    /// [`FileSource::set_on_change`]. Built on `futures-channel`, so it works with any async
+    /// runtime as a `Stream`.
+    pub fn subscribe(&self) -> UnboundedReceiver<T> {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .expect("error locking subscribers in FileSource");
+        subscribers.eq = Some(T::eq);
+        subscribers.senders.push(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+
+    use crate::TestSource;
+
+    #[test]
+    fn subscriber_notified_on_change() {
+        let source: TestSource<String, true> = TestSource::new("first");
+        source.refresh_value().unwrap();
+        let mut changes = source.subscribe();
+
+        source.refresh_value().unwrap();
+        source.write("second");
+        source.refresh_value().unwrap();
+
+        assert_eq!(block_on(changes.recv()).unwrap(), "second");
+        assert!(changes.try_recv().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use tempfile::TempDir;
+
+use crate::{FileSource, MockClock, Refreshable};
+
+const TEST_SOURCE_FILE_NAME: &str = "value";
+
+/// [`FileSource`] backed by a file in its own temporary directory and driven by a
+/// [`MockClock`], for testing code that depends on sources.
+///
+/// Dereferences to the wrapped source; the directory is removed on drop.
+pub struct TestSource<T: FromStr + Clone, const REQUIRED: bool>
+where
+    T::Err: std::fmt::Debug,
+{
+    source: FileSource<T, REQUIRED>,
+    clock: MockClock,
+    path: PathBuf,
+    _dir: TempDir,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool>
+    TestSource<T, REQUIRED>
+{
+    pub fn new(content: impl AsRef<[u8]>) -> Self {
+        let source = Self::missing();
+        source.write(content);
+        source
+    }
+
+    /// Creates the source without writing its file yet.
+    pub fn missing() -> Self {
+        let dir = tempfile::tempdir().expect("error creating temp dir for TestSource");
+        let path = dir.path().join(TEST_SOURCE_FILE_NAME);
+        let clock = MockClock::new();
+        let mut source = FileSource::from_path(path.clone());
+        source.set_clock(Arc::new(clock.clone()));
+
+        Self {
+            source,
+            clock,
+            path,
+            _dir: dir,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn clock(&self) -> &MockClock {
+        &self.clock
+    }
+
+    /// Replaces the file's content in place.
+    pub fn write(&self, content: impl AsRef<[u8]>) {
+        fs::write(&self.path, content).expect("error writing TestSource file");
+    }
+
+    /// Replaces the file by writing a new one and renaming it over, the way atomic updates
+    /// such as Kubernetes volume swaps do, so the source sees a new inode.
+    pub fn replace(&self, content: impl AsRef<[u8]>) {
+        let replacement = self.path.with_extension("new");
+        fs::write(&replacement, content).expect("error writing TestSource replacement");
+        fs::rename(&replacement, &self.path).expect("error renaming TestSource replacement");
+    }
+
+    pub fn remove(&self) {
+        fs::remove_file(&self.path).expect("error removing TestSource file");
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.clock.advance(duration);
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool> Deref
+    for TestSource<T, REQUIRED>
+{
+    type Target = FileSource<T, REQUIRED>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.source
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool> DerefMut
+    for TestSource<T, REQUIRED>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.source
+    }
+}
+
+/// Lets test sources join a [`crate::RefreshGroup`] like the source they wrap.
+impl<E, T, const REQUIRED: bool> Refreshable for TestSource<T, REQUIRED>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    T: FromStr<Err = E> + Clone + Send + Sync,
+{
+    fn path(&self) -> PathBuf {
+        self.source.path()
+    }
+
+    fn refresh(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.source.refresh()
+    }
+
+    fn generation(&self) -> u64 {
+        self.source.generation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValueSource;
+
+    #[test]
+    fn reads_written_content() {
+        let source: TestSource<u16, true> = TestSource::new("8080");
+
+        assert_eq!(source.value().unwrap(), 8080);
+    }
+
+    #[test]
+    fn refreshes_after_rewrite_and_advance() {
+        let mut source: TestSource<String, false> = TestSource::new("first");
+        source.set_refresh_interval(Some(Duration::from_secs(5)));
+        assert_eq!(source.value().unwrap(), Some("first".to_owned()));
+
+        source.write("second");
+        assert_eq!(source.value().unwrap(), Some("first".to_owned()));
+        source.advance(Duration::from_secs(6));
+        assert_eq!(source.value().unwrap(), Some("second".to_owned()));
+
+        source.remove();
+        source.advance(Duration::from_secs(6));
+        assert_eq!(source.value().unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replace_swaps_inode() {
+        use std::os::unix::fs::MetadataExt;
+
+        let source: TestSource<String, true> = TestSource::new("first");
+        let inode = fs::metadata(source.path()).unwrap().ino();
+
+        source.replace("second");
+        assert_ne!(fs::metadata(source.path()).unwrap().ino(), inode);
+        assert_eq!(source.value().unwrap(), "second");
+    }
+
+    #[test]
+    fn missing_until_written() {
+        let source: TestSource<String, true> = TestSource::missing();
+        assert!(source.value().is_err());
+
+        source.write("hello");
+        assert_eq!(source.value().unwrap(), "hello");
+    }
+}
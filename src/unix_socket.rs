@@ -0,0 +1,179 @@
+use std::{
+    io::{self, Read},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{Clock, RefreshFileSourceError, SystemClock, ValueError, ValueSource};
+
+/// Source reading its value from a Unix domain socket, e.g. one a sidecar serves the current
+/// config on: each refresh connects, reads the response up to EOF, trims and parses it.
+///
+/// A socket path that doesn't exist counts as a missing value; other connection failures are
+/// reported as [`RefreshFileSourceError::ConnectError`].
+pub struct UnixSocketSource<T: FromStr + Clone, const REQUIRED: bool>
+where
+    T::Err: std::fmt::Debug,
+{
+    path: PathBuf,
+    value: RwLock<Option<T>>,
+    refresh_interval: Option<Duration>,
+    last_refresh: RwLock<Option<Instant>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone, const REQUIRED: bool>
+    UnixSocketSource<T, REQUIRED>
+{
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            value: RwLock::new(None),
+            refresh_interval: None,
+            last_refresh: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn refresh_value(&self) -> Result<(), RefreshFileSourceError<E>> {
+        let value = match UnixStream::connect(&self.path) {
+            Ok(mut stream) => {
+                let mut response = String::new();
+                stream.read_to_string(&mut response)?;
+                Some(
+                    response
+                        .trim()
+                        .parse()
+                        .map_err(RefreshFileSourceError::ParseError)?,
+                )
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound && REQUIRED => {
+                return Err(RefreshFileSourceError::NoValue);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(RefreshFileSourceError::ConnectError(e)),
+        };
+
+        *self
+            .value
+            .write()
+            .expect("error locking value in UnixSocketSource") = value;
+        *self
+            .last_refresh
+            .write()
+            .expect("error locking last_refresh in UnixSocketSource") = Some(self.clock.now());
+        Ok(())
+    }
+
+    pub fn refresh_on_timeout(&self) -> Result<(), RefreshFileSourceError<E>> {
+        let needs_refresh = self
+            .last_refresh
+            .read()
+            .expect("error reading last_refresh in UnixSocketSource")
+            .is_none_or(|last_refresh| {
+                self.refresh_interval.is_some_and(|refresh_interval| {
+                    (last_refresh + refresh_interval) < self.clock.now()
+                })
+            });
+        if needs_refresh {
+            self.refresh_value()?;
+        }
+
+        Ok(())
+    }
+
+    fn cached(&self) -> Option<T> {
+        self.value
+            .read()
+            .expect("error reading value in UnixSocketSource")
+            .clone()
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<T, E>
+    for UnixSocketSource<T, true>
+{
+    fn value(&self) -> Result<T, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        self.cached().ok_or(ValueError::NoValue)
+    }
+}
+
+impl<E: std::fmt::Debug, T: FromStr<Err = E> + Clone> ValueSource<Option<T>, E>
+    for UnixSocketSource<T, false>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<E>> {
+        self.refresh_on_timeout()?;
+        Ok(self.cached())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, os::unix::net::UnixListener, thread};
+
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn reads_from_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("config.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = thread::spawn(move || {
+            for response in ["8080\n", "9090\n"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        let clock = MockClock::new();
+        let mut source: UnixSocketSource<u16, true> = UnixSocketSource::new(socket.clone());
+        source
+            .set_refresh_interval(Some(Duration::from_secs(10)))
+            .set_clock(Arc::new(clock.clone()));
+
+        assert_eq!(source.value().unwrap(), 8080);
+        assert_eq!(source.value().unwrap(), 8080);
+        clock.advance(Duration::from_secs(11));
+        assert_eq!(source.value().unwrap(), 9090);
+        server.join().unwrap();
+
+        // the socket file is left behind with nobody listening
+        clock.advance(Duration::from_secs(11));
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ConnectError(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn missing_socket() {
+        let optional: UnixSocketSource<u16, false> =
+            UnixSocketSource::new("sources/test-missing.sock".into());
+        assert_eq!(optional.value().unwrap(), None);
+
+        let required: UnixSocketSource<u16, true> =
+            UnixSocketSource::new("sources/test-missing.sock".into());
+        assert!(matches!(
+            required.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::NoValue
+            ))
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+use std::{fmt::Debug, ops::RangeInclusive};
+
+type Rule<T> = Box<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// Every failed rule's message, from validating a parsed value.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{}", .0.join("; "))]
+pub struct ValidationError(pub Vec<String>);
+
+/// Set of rules a parsed value must pass, attached with
+/// [`FileSource::set_validator`](crate::FileSource::set_validator).
+///
+/// All rules are checked, so the resulting error lists every failed rule. Alternatives, of
+/// which only one has to pass, are combined with [`Validator::any`] or [`Validator::or`].
+pub struct Validator<T> {
+    rules: Vec<Rule<T>>,
+}
+
+impl<T> Default for Validator<T> {
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<T> Validator<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        let errors: Vec<String> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule(value).err())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError(errors))
+        }
+    }
+}
+
+impl<T: 'static> Validator<T> {
+    /// Adds a rule returning an error message if the value is invalid.
+    pub fn custom(
+        mut self,
+        rule: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Validator passing if any of `validators` passes, failing with every alternative's
+    /// messages otherwise. Without alternatives it always fails.
+    pub fn any(validators: impl IntoIterator<Item = Validator<T>>) -> Self {
+        let validators: Vec<_> = validators.into_iter().collect();
+        Self::new().custom(move |value| {
+            let mut failures = Vec::with_capacity(validators.len());
+            for validator in &validators {
+                match validator.validate(value) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => failures.push(format!("({e})")),
+                }
+            }
+            Err(format!("no alternative passed: {}", failures.join(" or ")))
+        })
+    }
+
+    /// Validator passing if either `self` or `other` passes, see [`Validator::any`].
+    pub fn or(self, other: Validator<T>) -> Self {
+        Self::any([self, other])
+    }
+
+    pub fn range(self, range: RangeInclusive<T>) -> Self
+    where
+        T: PartialOrd + Debug + Send + Sync,
+    {
+        self.custom(move |value| {
+            if range.contains(value) {
+                Ok(())
+            } else {
+                Err(format!("value {value:?} is not within {range:?}"))
+            }
+        })
+    }
+}
+
+impl<T: AsRef<str> + 'static> Validator<T> {
+    pub fn non_empty(self) -> Self {
+        self.custom(|value| {
+            if value.as_ref().is_empty() {
+                Err("value must not be empty".to_owned())
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn matches(self, regex: regex::Regex) -> Self {
+        self.custom(move |value| {
+            if regex.is_match(value.as_ref()) {
+                Ok(())
+            } else {
+                Err(format!("value does not match {}", regex.as_str()))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileSource, RefreshFileSourceError, ValueError, ValueSource};
+
+    #[test]
+    fn combined_rules_report_all_failures() {
+        let mut source: FileSource<u16, true> = FileSource::from_path("sources/test-port".into());
+        source.set_validator(Validator::new().range(1..=1024).custom(|port| {
+            if port % 2 == 1 {
+                Ok(())
+            } else {
+                Err("port must be odd".to_owned())
+            }
+        }));
+
+        let Err(ValueError::RefreshFileSourceError(RefreshFileSourceError::ValidationError(
+            ValidationError(errors),
+        ))) = source.value()
+        else {
+            panic!("expected validation error");
+        };
+        assert_eq!(
+            errors,
+            ["value 8080 is not within 1..=1024", "port must be odd"]
+        );
+    }
+
+    #[test]
+    fn any_passes_if_one_passes() {
+        let validator = Validator::any([
+            Validator::new().range(1..=1024),
+            Validator::new().range(8000..=8999),
+            Validator::new().custom(|port: &u16| {
+                if *port % 2 == 1 {
+                    Ok(())
+                } else {
+                    Err("port must be odd".to_owned())
+                }
+            }),
+        ]);
+
+        assert!(validator.validate(&80).is_ok());
+        assert!(validator.validate(&8080).is_ok());
+        assert!(validator.validate(&9091).is_ok());
+        assert_eq!(
+            validator.validate(&9090),
+            Err(ValidationError(vec![
+                "no alternative passed: (value 9090 is not within 1..=1024) or (value 9090 is \
+                 not within 8000..=8999) or (port must be odd)"
+                    .to_owned()
+            ]))
+        );
+
+        let either = Validator::new()
+            .range(1..=1024)
+            .or(Validator::new().range(8000..=8999));
+        assert!(either.validate(&8080).is_ok());
+        assert!(either.validate(&9090).is_err());
+    }
+
+    #[test]
+    fn non_empty_passes() {
+        let mut source: FileSource<String, true> =
+            FileSource::from_path("sources/test-required".into());
+        source.set_validator(Validator::new().non_empty());
+
+        assert_eq!(source.value().unwrap(), "hello world!");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches_regex() {
+        let validator = Validator::<String>::new().matches(regex::Regex::new("^[a-z]+$").unwrap());
+
+        assert!(validator.validate(&"hello".to_owned()).is_ok());
+        assert_eq!(
+            validator.validate(&"Hello".to_owned()),
+            Err(ValidationError(vec![
+                "value does not match ^[a-z]+$".to_owned()
+            ]))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::{FileSource, RefreshFileSourceError, ValueError, ValueOutcome, ValueSource};
+
+pub type VersionParser<T, E> = Box<dyn Fn(&str) -> Result<T, E> + Send + Sync>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum VersionGateError<E: std::fmt::Debug> {
+    #[error("no parser registered for schema version {0:?}")]
+    UnknownVersion(String),
+    #[error("error parsing value of schema version {version:?}: {error:?}")]
+    Parse { version: String, error: E },
+}
+
+/// Source whose file format depends on a companion version file, e.g. during a rolling config
+/// migration. The contents are parsed by the parser registered for the current version.
+///
+/// The version file is only read while the value file has contents, a missing version file
+/// then fails with [`ValueError::NoValue`].
+pub struct VersionGatedSource<T, E, const REQUIRED: bool> {
+    version: FileSource<String, true>,
+    source: FileSource<String, REQUIRED>,
+    parsers: HashMap<String, VersionParser<T, E>>,
+}
+
+impl<T, E: std::fmt::Debug, const REQUIRED: bool> VersionGatedSource<T, E, REQUIRED> {
+    pub fn new(version: FileSource<String, true>, source: FileSource<String, REQUIRED>) -> Self {
+        Self {
+            version,
+            source,
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// Parse the contents with `parser` while the version file contains `version`.
+    pub fn add_version(
+        &mut self,
+        version: impl Into<String>,
+        parser: VersionParser<T, E>,
+    ) -> &mut Self {
+        self.parsers.insert(version.into(), parser);
+        self
+    }
+
+    pub fn version_mut(&mut self) -> &mut FileSource<String, true> {
+        &mut self.version
+    }
+
+    pub fn source_mut(&mut self) -> &mut FileSource<String, REQUIRED> {
+        &mut self.source
+    }
+
+    fn parsed(&self) -> Result<Option<T>, ValueError<VersionGateError<E>>> {
+        let ValueOutcome::Loaded(contents) = self.source.refreshed_raw()? else {
+            return Ok(None);
+        };
+        let ValueOutcome::Loaded(version) = self.version.refreshed_raw()? else {
+            return Err(ValueError::NoValue);
+        };
+
+        let parser = self.parsers.get(&version).ok_or_else(|| {
+            RefreshFileSourceError::ParseError(VersionGateError::UnknownVersion(version.clone()))
+        })?;
+        let value = parser(&contents).map_err(|error| {
+            RefreshFileSourceError::ParseError(VersionGateError::Parse { version, error })
+        })?;
+        Ok(Some(value))
+    }
+}
+
+impl<T, E: std::fmt::Debug> ValueSource<T, VersionGateError<E>> for VersionGatedSource<T, E, true> {
+    fn value(&self) -> Result<T, ValueError<VersionGateError<E>>> {
+        self.parsed()?.ok_or(ValueError::NoValue)
+    }
+}
+
+impl<T, E: std::fmt::Debug> ValueSource<Option<T>, VersionGateError<E>>
+    for VersionGatedSource<T, E, false>
+{
+    fn value(&self) -> Result<Option<T>, ValueError<VersionGateError<E>>> {
+        self.parsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::ParseIntError, time::Duration};
+
+    use super::*;
+
+    fn timeout_source(
+        version: &str,
+        contents: &str,
+    ) -> VersionGatedSource<Duration, ParseIntError, true> {
+        let mut source = VersionGatedSource::new(
+            FileSource::from_path(version.into()),
+            FileSource::from_path(contents.into()),
+        );
+        source
+            .add_version(
+                "1",
+                Box::new(|contents| contents.parse().map(Duration::from_secs)),
+            )
+            .add_version(
+                "2",
+                Box::new(|contents| {
+                    contents
+                        .trim_end_matches("ms")
+                        .parse()
+                        .map(Duration::from_millis)
+                }),
+            );
+        source
+    }
+
+    #[test]
+    fn dispatches_on_version() {
+        let v1 = timeout_source("sources/test-version-1", "sources/test-timeout-v1");
+        let v2 = timeout_source("sources/test-version-2", "sources/test-timeout-v2");
+
+        assert_eq!(v1.value().unwrap(), Duration::from_secs(30));
+        assert_eq!(v2.value().unwrap(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn unknown_version_is_error() {
+        let source = timeout_source("sources/test-version-3", "sources/test-timeout-v1");
+
+        assert!(matches!(
+            source.value(),
+            Err(ValueError::RefreshFileSourceError(
+                RefreshFileSourceError::ParseError(VersionGateError::UnknownVersion(version))
+            )) if version == "3"
+        ));
+    }
+}